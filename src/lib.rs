@@ -5,11 +5,20 @@ macro_rules! def_str_consts {
 }
 
 pub mod traits;
+pub mod parse_error;
 
 pub mod tsp {
     pub mod def;
+    pub mod edge_weight;
+    pub mod balance;
+    pub mod selection;
+    pub mod termination;
+    pub mod migration;
+    pub mod cooling;
     pub mod solver;
     pub mod random_solver;
+    pub mod tour;
+    pub mod tour_solver;
     pub mod solvers_factory;
     pub mod partial_path;
     pub mod picker;
@@ -20,6 +29,12 @@ pub mod tsp {
         pub mod cycle_simultaneous_picker;
         pub mod regret_picker;
     }
+    pub mod distance;
+    pub mod distances {
+        pub mod dense_distance;
+        pub mod on_the_fly_distance;
+        pub mod compact_distance;
+    }
     pub mod similarities {
         pub mod edge_similarity;
         pub mod vertex_similarity;
@@ -30,15 +45,28 @@ pub mod tsp {
         pub mod inter_cycle_transition;
         pub mod edges_transition;
         pub mod vertex_transition;
+        pub mod relocate_transition;
+        pub mod insert_transition;
+        pub mod remove_transition;
+        pub mod swap_transition;
+        pub mod or_opt_transition;
+        pub mod three_opt_transition;
+        pub mod cross_exchange_transition;
+        pub mod dont_look_bits;
+        pub mod transition_kind;
     }
     pub mod local_solvers;
     pub mod custom_solver;
     pub mod candidate_solver;
     pub mod memory_solver;
+    pub mod lin_kernighan_solver;
     pub mod multistart_solver;
     pub mod evolutionary_solver;
     pub mod convexity_checker;
     pub mod iterated_solver;
+    pub mod simulated_annealing_solver;
+    pub mod tabu_search_solver;
+    pub mod variable_neighborhood_solver;
 }
 pub mod primes {
     pub mod primes;
@@ -1,47 +1,129 @@
 use std::env;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use std::fs;
 use std::fs::File;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 use json;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
 
-use imo::utils::{Stat, contents, print_table_to_file, print_graph_to_file};
-use imo::traits::Instance;
+use imo::utils::{Stat, Trace, contents, print_table_to_file, print_graph_to_file, print_traces_to_csv, print_traces_to_json};
+use imo::traits::{Instance, Solver, Cost};
 use imo::tsp::def::{TSPInstance, TSPSolution};
 use imo::tsp::solvers_factory::SolversFactory;
+use imo::tsp::balance::Balance;
+use imo::tsp::selection::Selection;
+use imo::tsp::distance::DistanceKind;
+use imo::tsp::tour::write_tour;
+
+struct Run {
+    solution: TSPSolution,
+    score: Cost,
+    duration: Duration,
+    seed: u64,
+    trace: Trace,
+}
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
-        panic!("Usage {} config_path", args[0]);
+        panic!("Usage {} config_path [--jobs N]", args[0]);
     }
     let config = json::parse(&contents(&args[1])).unwrap();
 
     let mut scores = vec![vec![Stat::new(); config["instances"].len()]; config["algorithms"].len()];
     let mut times = vec![vec![Stat::new(); config["instances"].len()]; config["algorithms"].len()];
-    let mut best_solutions = vec![vec![TSPSolution::new(Vec::new(), Vec::new()); config["instances"].len()]; config["algorithms"].len()];
+    let mut best_solutions = vec![vec![TSPSolution::new(Vec::new()); config["instances"].len()]; config["algorithms"].len()];
+    let mut traces = vec![];
 
     let filepath = format!("res/{}", config["plots"].as_str().unwrap());
     let mut plot_file = File::create(&filepath).unwrap();
     let time_multiplier = config["time_multiplier"].as_f32().unwrap();
+    let seed = config["seed"].as_u64().unwrap_or_else(|| {
+        let seed = rand::thread_rng().gen();
+        println!(">>> Seed {}", seed);
+        seed
+    });
+
+    let instances: Vec<Option<TSPInstance>> = config["instances"].members()
+        .map(|instancename| load_instance(&config, instancename.as_str().unwrap()))
+        .collect();
+    let solvers: Vec<Box<dyn Solver<TSPInstance, TSPSolution>>> = config["algorithms"].members()
+        .map(SolversFactory::create_from_json)
+        .collect();
+
+    // every (instance, algorithm, iteration) is an independent job with its own seed
+    let mut jobs = vec![];
+    for (j, instance) in instances.iter().enumerate() {
+        if let Some(instance) = instance {
+            for i in 0..solvers.len() {
+                if !solvers[i].supports(instance) {
+                    eprintln!(">>> Skipping {} on {}: the instance is not supported",
+                              config["algorithms"][i]["name"], config["instances"][j]);
+                    continue;
+                }
+                for k in 0..config["iterations"].as_usize().unwrap() {
+                    jobs.push((j, i, k));
+                }
+            }
+        }
+    }
+    let runs = run_jobs(&jobs, no_jobs(&args, &config), |j, i, k| {
+        let instance = instances[j].as_ref().unwrap();
+        let instancename = config["instances"][j].as_str().unwrap();
+        let algorithmname = config["algorithms"][i]["name"].as_str().unwrap();
+        if k == 0 {
+            println!(">>> Running {} {}", instancename, algorithmname);
+        }
+        let seed = iteration_seed(&config, seed, k);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut trace = Trace::new(instancename, algorithmname, k, seed);
+        let start = Instant::now();
+        let solution = solvers[i].solve_observed(k, instance, &mut rng, &mut trace);
+        let duration = start.elapsed();
+        Run { score: instance.eval(&solution), solution, duration, seed, trace }
+    });
+
+    // the results are gathered in the order of the jobs, so they do not depend on how many ran at the same time
+    let mut best_seeds = vec![vec![seed; config["instances"].len()]; config["algorithms"].len()];
+    for ((j, i, _), run) in jobs.into_iter().zip(runs) {
+        let is_best = scores[i][j].update(run.score);
+        times[i][j].update(run.duration.as_secs_f32()*time_multiplier);
+        if is_best {
+            best_solutions[i][j] = run.solution;
+            best_seeds[i][j] = run.seed;
+        }
+        traces.push(run.trace);
+    }
 
     for (j, instancename) in config["instances"].members().enumerate() {
-        let filepath = format!("data/{}", &instancename.as_str().unwrap());
-        let instance = TSPInstance::parse_file(&filepath);
+        let instance = match &instances[j] {
+            Some(instance) => instance,
+            None => continue,
+        };
         for (i, algorithm) in config["algorithms"].members().enumerate() {
-            let solver = SolversFactory::create_from_json(algorithm);
-            println!(">>> Running {} {}", &instancename.as_str().unwrap(), algorithm["name"].as_str().unwrap());
-            for k in 0..config["iterations"].as_usize().unwrap() {
-                let start = Instant::now();
-                let solution = solver.solve(k, &instance);
-                let duration = start.elapsed();
-                let score = instance.eval(&solution);
-                let is_best = scores[i][j].update(score);
-                times[i][j].update(duration.as_secs_f32()*time_multiplier);
-                if is_best {
-                    best_solutions[i][j] = solution;
+            if scores[i][j].is_empty() {
+                continue;
+            }
+            println!(">>> Best {} {} {} (seed {})", &instancename.as_str().unwrap(), algorithm["name"].as_str().unwrap(),
+                     instance.eval(&best_solutions[i][j]), best_seeds[i][j]);
+            if !instance.is_feasible(&best_solutions[i][j]) {
+                eprintln!(">>> {} exceeds the capacity on {}", algorithm["name"].as_str().unwrap(), &instancename.as_str().unwrap());
+            }
+            if let Some(tours) = config["tours"].as_str() {
+                let name = format!("{}.{}", instancename.as_str().unwrap(), algorithm["name"].as_str().unwrap());
+                let path = format!("res/{}/{}.tour", tours, name);
+                let written = fs::create_dir_all(format!("res/{}", tours))
+                    .and_then(|_| File::create(&path))
+                    .and_then(|mut tour_file| write_tour(&mut tour_file, &name, instance, &best_solutions[i][j]));
+                if let Err(err) = written {
+                    eprintln!(">>> Cannot write {}: {}", path, err);
                 }
             }
             print_graph_to_file(&mut plot_file, algorithm["name"].as_str().unwrap(),
-                config["plots_scale"].as_f32().unwrap(), &instance, &best_solutions[i][j]);
+                config["plots_scale"].as_f32().unwrap(), instance, &best_solutions[i][j]);
         }
     }
 
@@ -52,5 +134,83 @@ fn main() {
 
     print_table_to_file(&mut file, &times, &config);
 
+    // convergence of every run, for plotting the best score against time or iterations
+    if let Some(name) = config["traces"].as_str() {
+        print_traces_to_csv(&mut File::create(format!("res/{}.csv", name)).unwrap(), &traces).unwrap();
+        print_traces_to_json(&mut File::create(format!("res/{}.json", name)).unwrap(), &traces).unwrap();
+    }
+
+}
+
+fn load_instance(config: &json::JsonValue, instancename: &str) -> Option<TSPInstance> {
+    let filepath = format!("data/{}", instancename);
+    let mut instance = match TSPInstance::parse_file(&filepath) {
+        Ok(instance) => instance,
+        Err(err) => {
+            eprintln!(">>> Skipping {}: {}", instancename, err);
+            return None;
+        }
+    };
+    if let Some(no_cycles) = config["cycles"].as_usize() {
+        instance.no_cycles = no_cycles;
+    }
+    if let Some(depot) = config["depot"].as_usize() {
+        if depot >= instance.dimension {
+            eprintln!(">>> Skipping {}: depot {} is out of range for {} vertices", instancename, depot, instance.dimension);
+            return None;
+        }
+        instance.depot = Some(depot);
+    } else if config["depot"] == true {
+        instance.depot = instance.depots.first().cloned();
+    }
+    if !config["selection"].is_null() {
+        instance.selection = Selection::from_json(&config["selection"]);
+    }
+    if !config["balance"].is_null() {
+        instance.balance = Balance::from_json(&config["balance"]);
+    }
+    if let Err(reason) = instance.check_balance().and_then(|_| instance.check_capacity()) {
+        eprintln!(">>> Skipping {}: {}", instancename, reason);
+        return None;
+    }
+    if let Some(distance) = config["distance"].as_str() {
+        if let Err(reason) = instance.use_distance(DistanceKind::parse(distance).expect("distance must be Dense, OnTheFly or Compact")) {
+            eprintln!(">>> Keeping the distance matrix of {}: {}", instancename, reason);
+        }
+    }
+    Some(instance)
+}
+
+// Runs given by --jobs N or the "jobs" key at the same time, a single one by default so that the times stay comparable
+fn no_jobs(args: &[String], config: &json::JsonValue) -> usize {
+    match args.iter().position(|arg| arg == "--jobs") {
+        Some(position) => args.get(position + 1).and_then(|jobs| jobs.parse().ok()).expect("--jobs needs a number"),
+        None => config["jobs"].as_usize().unwrap_or(1),
+    }
+}
+
+// Workers take the next job until none are left, the runs are returned in the order of the jobs
+fn run_jobs<F>(jobs: &[(usize, usize, usize)], no_jobs: usize, run: F) -> Vec<Run>
+    where F: Fn(usize, usize, usize) -> Run + Sync {
+    let next = AtomicUsize::new(0);
+    let runs = Mutex::new((0..jobs.len()).map(|_| None).collect::<Vec<Option<Run>>>());
+    thread::scope(|scope| {
+        for _ in 0..no_jobs.max(1) {
+            scope.spawn(|| loop {
+                let job = next.fetch_add(1, Ordering::SeqCst);
+                if job >= jobs.len() {
+                    break;
+                }
+                let (j, i, k) = jobs[job];
+                let result = run(j, i, k);
+                runs.lock().unwrap()[job] = Some(result);
+            });
+        }
+    });
+    runs.into_inner().unwrap().into_iter().map(|run| run.unwrap()).collect()
+}
 
-}
\ No newline at end of file
+// Iterations are seeded from the "seeds" list when it is given, otherwise from consecutive values after the run's seed
+fn iteration_seed(config: &json::JsonValue, seed: u64, iteration: usize) -> u64 {
+    config["seeds"][iteration].as_u64().unwrap_or_else(|| seed.wrapping_add(iteration as u64))
+}
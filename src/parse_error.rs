@@ -0,0 +1,60 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum ParseErrorKind {
+    Io(io::Error),
+    InvalidNumber(String),
+    InvalidNode(String),
+    MissingValue(String),
+    MissingKey(&'static str),
+    Unsupported(&'static str, String),
+    WrongCount(&'static str, usize, usize),
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+    pub file: String,
+    pub line: Option<usize>,
+    pub reason: ParseErrorKind,
+}
+
+impl ParseError {
+    pub fn new(file: &str, line: Option<usize>, reason: ParseErrorKind) -> ParseError {
+        ParseError {
+            file: String::from(file),
+            line,
+            reason,
+        }
+    }
+
+    pub fn at(file: &str, line: usize, reason: ParseErrorKind) -> ParseError {
+        ParseError::new(file, Some(line), reason)
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::Io(err) => write!(f, "cannot read file ({})", err),
+            ParseErrorKind::InvalidNumber(token) => write!(f, "invalid number '{}'", token),
+            ParseErrorKind::InvalidNode(token) => write!(f, "invalid node '{}'", token),
+            ParseErrorKind::MissingValue(what) => write!(f, "missing {}", what),
+            ParseErrorKind::MissingKey(key) => write!(f, "missing {}", key),
+            ParseErrorKind::Unsupported(key, value) => write!(f, "unsupported {} '{}'", key, value),
+            ParseErrorKind::WrongCount(what, expected, found) =>
+                write!(f, "expected {} {}, found {}", expected, what, found),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.file, line, self.reason),
+            None => write!(f, "{}: {}", self.file, self.reason),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -1,4 +1,5 @@
 use rand::Rng;
+use rand::rngs::StdRng;
 
 pub struct Primes {}
 
@@ -56,10 +57,9 @@ impl Primes {
         }
         res
     }
-    pub fn group_generator(p: usize) -> usize {
+    pub fn group_generator(p: usize, rng: &mut StdRng) -> usize {
         let fi = p-1;
         let divisors = Primes::get_prime_divisors(fi);
-        let mut rng = rand::thread_rng();
         loop {
             let g = rng.gen_range(1..fi);
             let mut flag = true;
@@ -74,8 +74,8 @@ impl Primes {
             }
         }
     }
-    pub fn group_generator_and_size(n: usize) -> (usize, usize) {
+    pub fn group_generator_and_size(n: usize, rng: &mut StdRng) -> (usize, usize) {
         let p = Primes::next_prime(n+1);
-        (Primes::group_generator(p), p)
+        (Primes::group_generator(p, rng), p)
     }
 }
\ No newline at end of file
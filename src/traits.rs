@@ -1,16 +1,45 @@
+use crate::parse_error::ParseError;
+use rand::rngs::StdRng;
+use std::time::{Duration, Instant};
+
+pub type Cost = i64;
+
 pub trait Solution {}
 
 pub trait Instance<O> where O: Solution {
-    fn eval(&self, solution: &O) -> f32;
+    fn eval(&self, solution: &O) -> Cost;
 
-    fn parse_file(file_name: &str) -> Self;
+    fn parse_file(file_name: &str) -> Result<Self, ParseError> where Self: Sized;
 }
 
 pub trait MetaInstance<O>: Instance<O> where O: Solution {
     fn neighbours(solution: &O) -> Vec<O>;
 }
 
-pub trait Solver<I, O> where I: Instance<O>, O: Solution {
-    fn solve(&self, start_vertex: usize, instance: &I) -> O;
-    fn solve_s(&self, start_vertex: usize, instance: &I, solution: O) -> O;
+pub trait Solver<I, O>: Send + Sync where I: Instance<O>, O: Solution {
+    fn solve(&self, start_vertex: usize, instance: &I, rng: &mut StdRng) -> O;
+    fn solve_s(&self, start_vertex: usize, instance: &I, solution: O, rng: &mut StdRng) -> O;
+
+    // Anytime solvers report every improvement of their best solution, the others only the final one
+    fn solve_observed(&self, start_vertex: usize, instance: &I, rng: &mut StdRng, observer: &mut dyn Observer) -> O {
+        let start = Instant::now();
+        let solution = self.solve(start_vertex, instance, rng);
+        observer.improved(start.elapsed(), 0, instance.eval(&solution));
+        solution
+    }
+
+    // Runs of instances a solver cannot handle, such as asymmetric ones, are skipped when they are set up
+    fn supports(&self, _instance: &I) -> bool {
+        true
+    }
+}
+
+pub trait Observer {
+    fn improved(&mut self, elapsed: Duration, iteration: usize, score: Cost);
+}
+
+pub struct NoObserver;
+
+impl Observer for NoObserver {
+    fn improved(&mut self, _: Duration, _: usize, _: Cost) {}
 }
@@ -0,0 +1,90 @@
+use json;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Balance {
+    #[default]
+    Equal,
+    Exact(Vec<usize>),
+    Ratio(f32),
+    Tolerance(usize),
+}
+
+impl Balance {
+    pub fn from_json(config: &json::JsonValue) -> Balance {
+        if config["type"] == "Exact" {
+            Balance::Exact(config["sizes"].members().map(|size| size.as_usize().unwrap()).collect())
+        } else if config["type"] == "Ratio" {
+            Balance::Ratio(config["max_ratio"].as_f32().unwrap())
+        } else if config["type"] == "Tolerance" {
+            Balance::Tolerance(config["max_difference"].as_usize().unwrap())
+        } else {
+            Balance::Equal
+        }
+    }
+
+    // Exact sizes have to give one size per cycle and add up to the n vertices spread over the k cycles,
+    // ratios and tolerances have to be reachable by some split of the n vertices
+    pub fn check(&self, n: usize, k: usize) -> Result<(), String> {
+        match self {
+            Balance::Exact(sizes) if sizes.len() != k =>
+                Err(format!("Exact balance gives {} sizes for {} cycles", sizes.len(), k)),
+            Balance::Exact(sizes) if sizes.iter().sum::<usize>() != n =>
+                Err(format!("Exact balance sizes sum up to {} instead of {}", sizes.iter().sum::<usize>(), n)),
+            Balance::Ratio(ratio) if !ratio.is_finite() || *ratio < 1.0 =>
+                Err(format!("Ratio balance needs a finite max_ratio of at least 1, got {}", ratio)),
+            Balance::Ratio(_) if n < k =>
+                Err(format!("Ratio balance needs a vertex in each of the {} cycles, only {} are spread", k, n)),
+            Balance::Tolerance(0) if k > 0 && !n.is_multiple_of(k) =>
+                Err(format!("Tolerance balance of 0 cannot split {} vertices evenly over {} cycles", n, k)),
+            _ => Ok(()),
+        }
+    }
+
+    // Cycle sizes the construction heuristics aim at, exact sizes that do not add up to the n selected vertices
+    // (a profit selection stopping early) are scaled to them
+    pub fn targets(&self, n: usize, k: usize) -> Vec<usize> {
+        match self {
+            Balance::Exact(sizes) if sizes.len() == k && sizes.iter().sum::<usize>() == n => sizes.clone(),
+            Balance::Exact(sizes) if sizes.len() == k && sizes.iter().sum::<usize>() > 0 => {
+                let total: usize = sizes.iter().sum();
+                let mut targets: Vec<usize> = sizes.iter().map(|size| size * n / total).collect();
+                // the rounding remainder goes to the cycles cut the most
+                let mut order: Vec<usize> = (0..k).collect();
+                order.sort_by_key(|&c| std::cmp::Reverse(sizes[c] * n % total));
+                let missing = n - targets.iter().sum::<usize>();
+                for &c in order.iter().take(missing) {
+                    targets[c] += 1;
+                }
+                targets
+            }
+            _ => (0..k).map(|c| n / k + if c < n % k { 1 } else { 0 }).collect(),
+        }
+    }
+
+    pub fn allows(&self, sizes: &[usize]) -> bool {
+        let min = *sizes.iter().min().unwrap();
+        let max = *sizes.iter().max().unwrap();
+        match self {
+            Balance::Equal => max - min <= 1,
+            Balance::Exact(targets) => targets.as_slice() == sizes,
+            Balance::Ratio(ratio) => min > 0 && max as f32 <= *ratio * min as f32,
+            Balance::Tolerance(difference) => max - min <= *difference,
+        }
+    }
+
+    pub fn allows_relocation(&self, perms: &[Vec<usize>], from: usize, to: usize) -> bool {
+        self.allows_changes(perms, &[(from, -1), (to, 1)])
+    }
+
+    // Whether the sizes are still allowed after every (cycle, change) pair is applied
+    pub fn allows_changes(&self, perms: &[Vec<usize>], changes: &[(usize, isize)]) -> bool {
+        let mut sizes: Vec<isize> = perms.iter().map(|perm| perm.len() as isize).collect();
+        for (cycle, change) in changes.iter() {
+            sizes[*cycle] += change;
+        }
+        if sizes.iter().any(|size| *size < 0) {
+            return false;
+        }
+        self.allows(&sizes.into_iter().map(|size| size as usize).collect::<Vec<usize>>())
+    }
+}
@@ -1,18 +1,7 @@
-use std::time::Instant;
+use rand::rngs::StdRng;
 
-use rand::{Rng, thread_rng};
-use rand::seq::SliceRandom;
-
-use crate::traits::{Instance, Solver};
+use crate::traits::{Instance, Solver, Cost};
 use crate::tsp::def::{TSPInstance, TSPSolution};
-use crate::tsp::neighborhoods::neighborhood::Neighborhood;
-use crate::tsp::neighborhoods::transition::Transition;
-use crate::tsp::solver::GreedySolver;
-use crate::tsp::pickers::regret_picker::RegretPicker;
-use std::collections::{BinaryHeap, BTreeSet, HashMap};
-use std::collections::HashSet;
-use std::cmp::{Ordering, max};
-use crate::utils::{random_combination, MinFloat};
 use crate::tsp::similarity::Similarity;
 
 pub struct ConvexityChecker {
@@ -38,30 +27,24 @@ impl ConvexityChecker {
 
 
 impl Solver<TSPInstance, TSPSolution> for ConvexityChecker {
-    fn solve(&self, _: usize, instance: &TSPInstance) -> TSPSolution {
+    fn solve(&self, _: usize, instance: &TSPInstance, rng: &mut StdRng) -> TSPSolution {
         println!(">>> ConvexityCheck");
         let mut vec = vec![];
-        let EMPTY = TSPSolution {
-            perm_a: vec![],
-            perm_b: vec![],
-            cycle: vec![],
-            order: vec![],
-        };
-        let mut best_solution = (f32::INFINITY, 0);
+        let mut best_solution = (Cost::MAX, 0);
         for i in 0..self.no_solutions {
-            let solution = self.solver.solve(i % instance.dimension, instance);
+            let solution = self.solver.solve(i % instance.dimension, instance, rng);
             let score = instance.eval(&solution);
             if score < best_solution.0 {
                 best_solution = (score, i);
             }
             vec.push(solution);
         }
-        let mut best_solution: (f32, &TSPSolution, usize) = (
+        let mut best_solution: (Cost, &TSPSolution, usize) = (
             best_solution.0,
             &vec[best_solution.1],
             best_solution.1);
 
-        let solution = self.best_solver.solve(0, instance);
+        let solution = self.best_solver.solve(0, instance, rng);
         if instance.eval(&solution) < best_solution.0 {
             best_solution = (instance.eval(&solution), &solution, 0);
         }
@@ -93,7 +76,11 @@ impl Solver<TSPInstance, TSPSolution> for ConvexityChecker {
         vec.remove(best_solution.2)
     }
 
-    fn solve_s(&self, start_vertex: usize, instance: &TSPInstance, solution: TSPSolution) -> TSPSolution {
+    fn solve_s(&self, _start_vertex: usize, _instance: &TSPInstance, _solution: TSPSolution, _rng: &mut StdRng) -> TSPSolution {
         unimplemented!()
     }
+
+    fn supports(&self, instance: &TSPInstance) -> bool {
+        self.best_solver.supports(instance) && self.solver.supports(instance)
+    }
 }
@@ -0,0 +1,54 @@
+use json;
+
+// How the temperature of simulated annealing falls after every epoch of moves
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cooling {
+    // multiplies the temperature by alpha
+    Geometric(f64),
+    // lowers the temperature by the given fraction of the initial one, down to zero
+    Linear(f64),
+    // geometric cooling that heats up to reheat times the initial temperature after
+    // patience epochs without a new best solution
+    Adaptive { alpha: f64, patience: usize, reheat: f64 },
+}
+
+impl Default for Cooling {
+    fn default() -> Self {
+        Cooling::Geometric(0.95)
+    }
+}
+
+impl Cooling {
+    pub fn from_json(config: &json::JsonValue) -> Cooling {
+        if config.is_null() {
+            Cooling::default()
+        } else if config["type"] == "Geometric" {
+            Cooling::Geometric(config["alpha"].as_f64().unwrap())
+        } else if config["type"] == "Linear" {
+            Cooling::Linear(config["fraction"].as_f64().unwrap())
+        } else if config["type"] == "Adaptive" {
+            Cooling::Adaptive {
+                alpha: config["alpha"].as_f64().unwrap(),
+                patience: config["patience"].as_usize().unwrap(),
+                reheat: config["reheat"].as_f64().unwrap(),
+            }
+        } else {
+            panic!("Unknown cooling schedule {}", config["type"])
+        }
+    }
+
+    // Temperature for the next epoch, stagnation counts the epochs since the best solution last improved
+    pub fn next(&self, temperature: f64, initial: f64, stagnation: usize) -> f64 {
+        match self {
+            Cooling::Geometric(alpha) => temperature * alpha,
+            Cooling::Linear(fraction) => (temperature - fraction * initial).max(0.0),
+            Cooling::Adaptive { alpha, patience, reheat } => {
+                if stagnation > 0 && stagnation.is_multiple_of(*patience) {
+                    initial * reheat
+                } else {
+                    temperature * alpha
+                }
+            }
+        }
+    }
+}
@@ -1,22 +1,24 @@
-use std::time::Instant;
-
-use rand::{Rng, thread_rng};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 
-use crate::traits::{Instance, Solver};
+use crate::traits::{Instance, Solver, Cost, Observer, NoObserver};
 use crate::tsp::def::{TSPInstance, TSPSolution};
 use crate::tsp::neighborhoods::neighborhood::Neighborhood;
-use crate::tsp::neighborhoods::transition::Transition;
-use crate::tsp::solver::GreedySolver;
-use crate::tsp::pickers::regret_picker::RegretPicker;
-use std::collections::{BinaryHeap, BTreeSet, HashMap};
-use std::collections::HashSet;
-use std::cmp::{Ordering, max};
-use crate::utils::{random_combination, MinFloat};
+use crate::tsp::neighborhoods::transition_kind::TransitionKind;
+use std::collections::{BinaryHeap, HashMap};
+use std::cmp::Ordering;
+use crate::utils::{random_combination, MinCost};
+use crate::tsp::termination::{Termination, Progress};
+use crate::tsp::migration::Migration;
+use std::sync::mpsc;
+use std::sync::mpsc::{Sender, Receiver};
+use std::thread;
+use std::time::Duration;
 
 pub struct Candidate {
     solution: TSPSolution,
-    distance: f32,
+    distance: Cost,
     inbred_count: usize,
     cross_count: usize,
 }
@@ -33,8 +35,7 @@ impl Ord for Candidate {
     fn cmp(&self, other: &Self) -> Ordering {
         other
             .distance
-            .partial_cmp(&self.distance)
-            .unwrap()
+            .cmp(&self.distance)
     }
 }
 
@@ -47,8 +48,8 @@ impl PartialOrd for Candidate {
 
 pub struct Population {
     pop_registry: Vec<Candidate>,
-    pop_queue: BinaryHeap<(MinFloat, usize)>,
-    score_map: HashMap<usize, usize>,
+    pop_queue: BinaryHeap<(MinCost, usize)>,
+    score_map: HashMap<Cost, usize>,
 }
 
 impl Population {
@@ -66,13 +67,13 @@ impl Population {
 
     pub fn register(&mut self, candidate: Candidate) {
         let position = self.pop_registry.len();
-        self.score_map.insert(candidate.distance.clone() as usize, position);
-        self.pop_queue.push((MinFloat(candidate.distance.clone()), position));
+        self.score_map.insert(candidate.distance, position);
+        self.pop_queue.push((MinCost(candidate.distance), position));
         self.pop_registry.push(candidate);
     }
 
     pub fn inbred(&mut self, candidate: &Candidate) {
-        let index = self.score_map.get(&(candidate.distance as usize)).unwrap();
+        let index = self.score_map.get(&candidate.distance).unwrap();
         self.pop_registry[*index].inbred_count += 1;
         self.pop_registry[*index].cross_count += 1;
     }
@@ -83,18 +84,18 @@ impl Population {
 
     pub fn replace(&mut self, candidate: Candidate) {
         let popped_candidate = self.pop_queue.pop().unwrap();
-        self.score_map.remove(&(self.pop_registry[popped_candidate.1].distance as usize));
+        self.score_map.remove(&self.pop_registry[popped_candidate.1].distance);
         self.pop_registry[popped_candidate.1] = candidate;
-        self.pop_queue.push((MinFloat(self.pop_registry[popped_candidate.1].distance.clone()), popped_candidate.1));
-        self.score_map.insert(self.pop_registry[popped_candidate.1].distance.clone() as usize, popped_candidate.1);
+        self.pop_queue.push((MinCost(self.pop_registry[popped_candidate.1].distance), popped_candidate.1));
+        self.score_map.insert(self.pop_registry[popped_candidate.1].distance, popped_candidate.1);
     }
 
-    pub fn threshold(&self) -> f32 {
+    pub fn threshold(&self) -> Cost {
         self.pop_queue.peek().unwrap().0.0
     }
 
     pub fn has(&self, candidate: &Candidate) -> bool {
-        self.score_map.contains_key(&(candidate.distance as usize))
+        self.score_map.contains_key(&candidate.distance)
     }
 
     pub fn get(&self, index: usize) -> &Candidate {
@@ -113,114 +114,107 @@ impl Population {
         self.pop_queue.clear();
         self.score_map.clear();
         for i in 0..self.size() {
-            self.pop_queue.push((MinFloat(self.pop_registry[i].distance), i));
-            self.score_map.insert(self.pop_registry[i].distance as usize, i);
+            self.pop_queue.push((MinCost(self.pop_registry[i].distance), i));
+            self.score_map.insert(self.pop_registry[i].distance, i);
         }
     }
 
-    pub fn join(&mut self, population: &mut Population) {
-        for i in 0..population.pop_registry.len() {
-            if !self.has(&population.pop_registry[0]) {
-                self.pop_registry.push(population.pop_registry.remove(0));
+    // Copies of the best candidates, sent to other islands
+    pub fn elite(&self, count: usize) -> Vec<Candidate> {
+        let mut order: Vec<usize> = (0..self.size()).collect();
+        order.sort_by_key(|i| self.pop_registry[*i].distance);
+        order.into_iter().take(count).map(|i| {
+            // a clone does not keep the cycles and orders crossing over relies on
+            let solution = &self.pop_registry[i].solution;
+            Candidate {
+                solution: TSPSolution::with_dimension(solution.perms.clone(), solution.cycle.len()),
+                distance: self.pop_registry[i].distance,
+                inbred_count: 0,
+                cross_count: 0,
             }
-        }
+        }).collect()
+    }
+}
+
+// Forwards improvements found on an island thread to the observer of the whole run
+struct Improvements(Sender<(Duration, usize, Cost)>);
+
+impl Observer for Improvements {
+    fn improved(&mut self, elapsed: Duration, iteration: usize, score: Cost) {
+        let _ = self.0.send((elapsed, iteration, score));
     }
 }
 
 pub struct CustomSolver {
     local_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
     construction_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
-    time: f32,
+    termination: Termination,
     no_populations: usize,
     population_size: usize,
     steps_to_mutation: usize,
-    transition: fn() -> Vec<Box<dyn Transition>>,
+    migration: Migration,
+    transition: Vec<TransitionKind>,
 }
 
 impl CustomSolver {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(local_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
                construction_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
-               time: f32,
+               termination: Termination,
                no_populations: usize,
                population_size: usize,
                steps_to_mutation: usize,
-               transition: fn() -> Vec<Box<dyn Transition>>) -> CustomSolver {
+               migration: Migration,
+               transition: Vec<TransitionKind>) -> CustomSolver {
         CustomSolver {
             local_solver,
             construction_solver,
-            time,
+            termination,
             no_populations,
             population_size,
             steps_to_mutation,
+            migration,
             transition,
         }
     }
 
-    fn cross_over(&self, parents: (usize, usize), instance: &TSPInstance, population: &mut Population) -> Option<Candidate> {
+    fn cross_over(&self, parents: (usize, usize), instance: &TSPInstance, population: &mut Population, progress: &mut Progress, rng: &mut StdRng) -> Option<Candidate> {
         let candidate_a = population.get(parents.0);
         let candidate_b = population.get(parents.1);
-        let mut new_perm_a = vec![];
-        let mut new_perm_b = vec![];
-        let mut noswap_count = 0;
-
-        for vertex_i in 0..instance.dimension {
-            if candidate_a.solution.cycle[vertex_i] == candidate_b.solution.cycle[vertex_i] {
-                noswap_count += 1;
-            }
-        }
-        let mut a_cycle_dest = 0;
-        let mut a_perm_a = &candidate_a.solution.perm_a;
-        let mut a_perm_b = &candidate_a.solution.perm_b;
-
-        let b_perm_a = &candidate_b.solution.perm_a;
-        let b_perm_b = &candidate_b.solution.perm_b;
+        let mapping = candidate_a.solution.matching(&candidate_b.solution);
         let b_order = &candidate_b.solution.order;
         let b_cycle = &candidate_b.solution.cycle;
+        let mut new_perms = vec![];
+
+        for (a_cycle, a_perm) in candidate_a.solution.perms.iter().enumerate() {
+            let b_cycle_dest = mapping[a_cycle];
+            let b_perm = &candidate_b.solution.perms[b_cycle_dest];
+            let mut new_perm = vec![];
+            for a_vert_i in 0..a_perm.len() {
+                let g_vert = a_perm[a_vert_i];
+                if b_cycle[g_vert] == b_cycle_dest
+                    && (b_perm[(b_order[g_vert] + 1) % b_perm.len()] == a_perm[(a_vert_i + 1) % a_perm.len()]
+                    || b_perm[(b_order[g_vert] + b_perm.len() - 1) % b_perm.len()] == a_perm[(a_vert_i + 1) % a_perm.len()]) {
+                    let a = g_vert;
+                    if new_perm.is_empty() || a != *new_perm.last().unwrap() {
+                        new_perm.push(g_vert);
+                    }
 
-        if noswap_count < instance.dimension / 2 {
-            a_perm_a = &candidate_a.solution.perm_b;
-            a_perm_b = &candidate_a.solution.perm_a;
-            a_cycle_dest = 1;
-        }
-        for a_vert_i in 0..a_perm_a.len() {
-            let g_vert = a_perm_a[a_vert_i];
-            if b_cycle[g_vert] == a_cycle_dest
-                && (b_perm_a[(b_order[g_vert] + 1) % a_perm_a.len()] == a_perm_a[(a_vert_i + 1) % a_perm_a.len()]
-                || b_perm_a[(b_order[g_vert] + a_perm_a.len() - 1) % a_perm_a.len()] == a_perm_a[(a_vert_i + 1) % a_perm_a.len()]) {
-                let a = g_vert;
-                if new_perm_a.is_empty() || a != *new_perm_a.last().unwrap() {
-                    new_perm_a.push(g_vert);
-                }
-
-                let a = a_perm_a[(a_vert_i + 1) % a_perm_a.len()];
-                if new_perm_a.is_empty() || a != *new_perm_a.first().unwrap() {
-                    new_perm_a.push(a);
-                }
-            }
-        }
-
-        for a_vert_i in 0..a_perm_b.len() {
-            let g_vert = a_perm_b[a_vert_i];
-            if b_cycle[g_vert] == 1 - a_cycle_dest
-                && (b_perm_b[(b_order[g_vert] + 1) % b_perm_b.len()] == a_perm_b[(a_vert_i + 1) % a_perm_b.len()]
-                || b_perm_b[(b_order[g_vert] + b_perm_b.len() - 1) % b_perm_b.len()] == a_perm_b[(a_vert_i + 1) % a_perm_b.len()]) {
-                let a = g_vert;
-                if new_perm_b.is_empty() || a != *new_perm_b.last().unwrap() {
-                    new_perm_b.push(g_vert);
-                }
-
-                let a = a_perm_b[(a_vert_i + 1) % a_perm_b.len()];
-                if new_perm_b.is_empty() || a != *new_perm_b.first().unwrap() {
-                    new_perm_b.push(a);
+                    let a = a_perm[(a_vert_i + 1) % a_perm.len()];
+                    if new_perm.is_empty() || a != *new_perm.first().unwrap() {
+                        new_perm.push(a);
+                    }
                 }
             }
+            new_perms.push(new_perm);
         }
 
-        let mut solution = TSPSolution { perm_a: new_perm_a, perm_b: new_perm_b, cycle: vec![], order: vec![] };
-        let mut solution = self.construction_solver.solve_s(0, instance, solution);
+        let solution = TSPSolution { perms: new_perms, cycle: vec![], order: vec![] };
+        let mut solution = self.construction_solver.solve_s(0, instance, solution, rng);
         solution.reorder();
-        let mut solution = self.local_solver.solve_s(0, instance, solution);
+        let solution = self.local_solver.solve_s(0, instance, solution, rng);
         let distance = instance.eval(&solution);
+        progress.evaluation(distance);
 
         let f = distance == candidate_a.distance || distance == candidate_b.distance;
 
@@ -245,10 +239,11 @@ impl CustomSolver {
         }
     }
 
-    fn new_candidate(&self, start_vertex: usize, instance: &TSPInstance) -> Candidate {
-        let solution = self.construction_solver.solve(4 + start_vertex, instance);
-        let solution = self.local_solver.solve_s(start_vertex, instance, solution);
+    fn new_candidate(&self, start_vertex: usize, instance: &TSPInstance, progress: &mut Progress, rng: &mut StdRng) -> Candidate {
+        let solution = self.construction_solver.solve(4 + start_vertex, instance, rng);
+        let solution = self.local_solver.solve_s(start_vertex, instance, solution, rng);
         let distance = instance.eval(&solution);
+        progress.evaluation(distance);
         let inbred_count = 0;
         let cross_count = 0;
 
@@ -260,26 +255,106 @@ impl CustomSolver {
         }
     }
 
-    fn perturb(&self, instance: &TSPInstance, mut solution: TSPSolution, neighborhood: &mut Neighborhood, perturb_size: usize) -> TSPSolution {
-        solution.perm_a = solution.perm_a
-            .choose_multiple(&mut rand::thread_rng(), solution.perm_a.len() - perturb_size).cloned().collect();
-        solution.perm_b = solution.perm_b
-            .choose_multiple(&mut rand::thread_rng(), solution.perm_b.len() - perturb_size).cloned().collect();
+    fn include(&self, candidate: Candidate, population: &mut Population, best_solution: &mut (Cost, TSPSolution)) {
+        if population.has(&candidate) {
+            population.inbred(&candidate);
+        } else if population.should_include(&candidate) {
+            if candidate.distance < best_solution.0 {
+                *best_solution = (candidate.distance, candidate.solution.clone());
+            }
+            population.replace(candidate);
+        }
+    }
+
+    // A single island evolving on its own thread, returns its best solution once the termination criterion is reached
+    fn evolve_island(&self, island: usize, instance: &TSPInstance, senders: &[Sender<Vec<Candidate>>], receivers: &[Receiver<Vec<Candidate>>],
+                     observer: &mut dyn Observer, rng: &mut StdRng) -> (Cost, TSPSolution) {
+        let mut progress = Progress::observed(observer);
+        let mut population = Population::new();
+        let start_each = instance.dimension / self.no_populations;
+        let step_each = instance.dimension / self.no_populations / self.population_size;
+        for start_i in 0..self.population_size / self.no_populations {
+            population.register(self.new_candidate(island * start_each + start_i * step_each, instance, &mut progress, rng));
+        }
+        let best = population.elite(1).pop().unwrap();
+        let mut best_solution = (best.distance, best.solution);
+        let mut neighborhood = Neighborhood::new(TransitionKind::create_all(&self.transition), &best_solution.1, true, rng);
+
+        let mut generation = 0;
+        while !self.termination.reached(&progress) {
+            let size = population.size();
+            let parents: Vec<(usize, usize)> = if size * (size - 1) / 2 < self.steps_to_mutation {
+                (0..size - 1).flat_map(|parent_i| (parent_i + 1..size).map(move |parent_j| (parent_i, parent_j))).collect()
+            } else {
+                (0..self.steps_to_mutation).map(|_| random_combination(size, rng)).collect()
+            };
+            for parents in parents {
+                progress.iteration();
+                if let Some(candidate) = self.cross_over(parents, instance, &mut population, &mut progress, rng) {
+                    self.include(candidate, &mut population, &mut best_solution);
+                }
+                if self.termination.reached(&progress) {
+                    return best_solution;
+                }
+            }
+            self.mutate(instance, &mut population, &mut neighborhood, &mut progress, rng);
+
+            generation += 1;
+            if self.migration.due(generation) {
+                self.migrate(island, &mut population, senders, receivers, rng);
+            }
+        }
+
+        best_solution
+    }
+
+    // Every island sends one message to each other island per migration, empty unless the other one is a target,
+    // and then waits for one message from each of them in a fixed order, so the exchange does not depend on timing
+    fn migrate(&self, island: usize, population: &mut Population, senders: &[Sender<Vec<Candidate>>], receivers: &[Receiver<Vec<Candidate>>], rng: &mut StdRng) {
+        let targets = self.migration.targets(island, senders.len(), rng);
+        for (other, sender) in senders.iter().enumerate() {
+            if other != island {
+                let migrants = if targets.contains(&other) { population.elite(self.migration.migrants) } else { vec![] };
+                // the other island may have finished already
+                let _ = sender.send(migrants);
+            }
+        }
+        for (other, receiver) in receivers.iter().enumerate() {
+            if other == island {
+                continue;
+            }
+            // a finished island dropped its senders and sends nothing more
+            if let Ok(migrants) = receiver.recv() {
+                for candidate in migrants {
+                    if !population.has(&candidate) && population.should_include(&candidate) {
+                        population.replace(candidate);
+                    }
+                }
+            }
+        }
+    }
+
+    fn perturb(&self, _instance: &TSPInstance, mut solution: TSPSolution, _neighborhood: &mut Neighborhood, perturb_size: usize, rng: &mut StdRng) -> TSPSolution {
+        for perm in solution.perms.iter_mut() {
+            *perm = perm
+                .choose_multiple(rng, perm.len().saturating_sub(perturb_size)).cloned().collect();
+        }
         solution
     }
 
-    fn mutate(&self, instance: &TSPInstance, population: &mut Population, neighborhood: &mut Neighborhood) {
+    fn mutate(&self, instance: &TSPInstance, population: &mut Population, neighborhood: &mut Neighborhood, progress: &mut Progress, rng: &mut StdRng) {
         for candidate in population.pop_registry.iter_mut() {
             let inbreds = candidate.inbred_count as f32;
             let total = candidate.cross_count as f32;
             if candidate.cross_count != 0 && candidate.inbred_count != 0 {
                 let mx = if inbreds / total + 0.1 < 0.8 { inbreds / total + 0.1 } else { 0.8 };
-                let perturb_size = ((instance.dimension as f32) * thread_rng().gen_range(0.1..mx)) as usize / 2;
-                let solution = self.perturb(instance, candidate.solution.clone(), neighborhood, perturb_size);
-                let solution = self.construction_solver.solve_s(0, instance, solution);
+                let perturb_size = ((instance.dimension as f32) * rng.gen_range(0.1..mx)) as usize / instance.no_cycles;
+                let solution = self.perturb(instance, candidate.solution.clone(), neighborhood, perturb_size, rng);
+                let solution = self.construction_solver.solve_s(0, instance, solution, rng);
                 candidate.solution = solution;
                 candidate.solution.reorder();
                 candidate.distance = instance.eval(&candidate.solution);
+                progress.evaluation(candidate.distance);
                 candidate.inbred_count = 0;
                 candidate.cross_count = 0;
             }
@@ -291,113 +366,50 @@ impl CustomSolver {
 
 
 impl Solver<TSPInstance, TSPSolution> for CustomSolver {
-    fn solve(&self, _: usize, instance: &TSPInstance) -> TSPSolution {
-        println!("=====");
-        let EMPTY = TSPSolution {
-            perm_a: vec![],
-            perm_b: vec![],
-            cycle: vec![],
-            order: vec![],
-        };
-        let start = Instant::now();
-
-        let mut populations = vec![];
-        let mut best_solution: (f32, (usize, usize)) = (f32::INFINITY, (0, 0));
-        for i in 0..self.no_populations {
-            println!("New population");
-            let mut population = Population::new();
-
-            let start_each = instance.dimension / self.no_populations;
-            let step_each = instance.dimension / self.no_populations / self.population_size;
-            for start_i in 0..self.population_size / self.no_populations {
-                let candidate = self.new_candidate(i * start_each + start_i * step_each, instance);
-                if candidate.distance < best_solution.0 {
-                    best_solution = (instance.eval(&candidate.solution), (i, start_i));
-                    println!(">> {}", best_solution.0);
-                }
-                population.register(candidate);
-            }
-
-            populations.push(population);
-        }
+    fn solve(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng) -> TSPSolution {
+        self.solve_observed(start_vertex, instance, rng, &mut NoObserver)
+    }
 
-        let mut best_solution = (best_solution.0, populations[best_solution.1.0]
-            .get(best_solution.1.0).solution.clone());
-        let mut neighborhood = Neighborhood::new((self.transition)(), &best_solution.1, true);
-        let island_steps = 4;
-        while start.elapsed().as_secs_f32() * 1000.0 < self.time {
-            for population_i in 0..populations.len() {
-                let population = &mut populations[population_i];
-                for island_step in 0..island_steps {
-                    if population.size() * (population.size() - 1) / 2 < self.steps_to_mutation {
-                        for parent_i in 0..population.size() - 1 {
-                            for parent_j in parent_i + 1..population.size() {
-                                match self.cross_over((parent_i, parent_j), instance, population) {
-                                    Some(candidate) => {
-                                        if population.has(&candidate) {
-                                            population.inbred(&candidate);
-                                        } else if population.should_include(&candidate) {
-                                            println!("{} {}", candidate.distance, population.threshold());
-                                            if candidate.distance < best_solution.0 {
-                                                best_solution = (instance.eval(&candidate.solution), candidate.solution.clone());
-                                                println!(">> {}", best_solution.0);
-                                            }
-                                            population.replace(candidate);
-                                        }
-                                    }
-                                    None => {}
-                                }
-                                if start.elapsed().as_secs_f32() >= self.time {
-                                    return best_solution.1;
-                                }
-                            }
-                        }
-                    } else {
-                        for step_i in 0..self.steps_to_mutation {
-                            match self.cross_over(random_combination(population.size()), instance, population) {
-                                Some(candidate) => {
-                                    if population.has(&candidate) {
-                                        population.inbred(&candidate);
-                                    } else if population.should_include(&candidate) {
-                                        println!("{} {}", candidate.distance, population.threshold());
-                                        if candidate.distance < best_solution.0 {
-                                            best_solution = (instance.eval(&candidate.solution), candidate.solution.clone());
-                                            println!(">> {}", best_solution.0);
-                                        }
-                                        population.replace(candidate);
-                                    }
-                                }
-                                None => {}
-                            }
-                            if start.elapsed().as_secs_f32() >= self.time {
-                                return best_solution.1;
-                            }
-                        }
-                    }
-                    if island_step != island_steps - 1 {
-                        println!("Mutating");
-                        self.mutate(instance, population, &mut neighborhood);
-                    }
-                }
+    fn solve_observed(&self, _: usize, instance: &TSPInstance, rng: &mut StdRng, observer: &mut dyn Observer) -> TSPSolution {
+        let seeds: Vec<u64> = (0..self.no_populations).map(|_| rng.gen()).collect();
+        // a channel for every pair of islands, senders[from][to] and receivers[to][from]
+        let (senders, channels): (Vec<Vec<_>>, Vec<Vec<_>>) = (0..self.no_populations)
+            .map(|_| (0..self.no_populations).map(|_| mpsc::channel()).unzip())
+            .unzip();
+        let mut receivers: Vec<Vec<_>> = (0..self.no_populations).map(|_| vec![]).collect();
+        for row in channels {
+            for (to, receiver) in row.into_iter().enumerate() {
+                receivers[to].push(receiver);
             }
-            println!("Joining");
-            if populations.len() / 2 > 0 {
-                let half_index = populations.len() / 2;
-                for population_i in 0..half_index {
-                    let mut population = populations.remove(half_index);
-                    populations[population_i].join(&mut population);
-                    while populations[population_i].size() < half_index * 2 {
-                        populations[population_i].register(self.new_candidate(rand::thread_rng().gen_range(0..instance.dimension), instance))
-                    }
-                    populations[population_i].rehash();
+        }
+        let (improvement_sender, improvements) = mpsc::channel();
+        let best_solution = thread::scope(|scope| {
+            let handles: Vec<_> = senders.into_iter().zip(receivers).enumerate().map(|(island, (senders, receivers))| {
+                let mut improvements = Improvements(improvement_sender.clone());
+                let seed = seeds[island];
+                scope.spawn(move || self.evolve_island(island, instance, &senders, &receivers, &mut improvements,
+                                                       &mut StdRng::seed_from_u64(seed)))
+            }).collect();
+            drop(improvement_sender);
+            // islands report their own improvements, the loop ends once all of them finished
+            let mut best = Cost::MAX;
+            for (elapsed, iteration, score) in improvements {
+                if score < best {
+                    best = score;
+                    observer.improved(elapsed, iteration, score);
                 }
             }
-        }
+            handles.into_iter().map(|handle| handle.join().unwrap()).min_by_key(|best_solution| best_solution.0).unwrap()
+        });
 
-        best_solution.1
+        TSPSolution::with_dimension(best_solution.1.perms, instance.dimension)
     }
 
-    fn solve_s(&self, start_vertex: usize, instance: &TSPInstance, solution: TSPSolution) -> TSPSolution {
+    fn solve_s(&self, _start_vertex: usize, _instance: &TSPInstance, _solution: TSPSolution, _rng: &mut StdRng) -> TSPSolution {
         unimplemented!()
     }
+
+    fn supports(&self, instance: &TSPInstance) -> bool {
+        self.local_solver.supports(instance) && self.construction_solver.supports(instance)
+    }
 }
@@ -1,90 +1,124 @@
 use crate::traits::Instance;
-use crate::traits::Solution;
-use crate::utils::contents;
+use crate::traits::{Solution, Cost};
+use crate::parse_error::{ParseError, ParseErrorKind};
+use std::fs;
+use std::str::FromStr;
+use crate::tsp::edge_weight::{EdgeWeightType, EdgeWeightFormat};
+use crate::tsp::balance::Balance;
+use crate::tsp::selection::Selection;
+use crate::tsp::distance::{Distance, DistanceKind};
+use crate::tsp::distances::dense_distance::DenseDistance;
+use crate::tsp::distances::compact_distance::CompactDistance;
+use crate::tsp::distances::on_the_fly_distance::OnTheFlyDistance;
+use crate::utils::best_assignment;
 
-#[derive(Debug)]
+// Cost of every unit of demand above the capacity of a cycle, large enough for any overload to outweigh distances
+pub const OVERLOAD_PENALTY: Cost = 1_000_000;
+
+#[derive(Debug, Clone)]
 pub struct City {
     id: usize,
     label: String,
-    x: f32,
-    y: f32,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
 }
 
 impl City {
     pub fn get_coord(&self) -> (f32, f32) {
-        (self.x, self.y)
+        (self.x as f32, self.y as f32)
     }
 }
 
 pub struct TSPSolution {
-    pub perm_a: Vec<usize>,
-    pub perm_b: Vec<usize>,
+    pub perms: Vec<Vec<usize>>,
     pub cycle: Vec<usize>,
     pub order: Vec<usize>,
 }
 
+// cycle of a vertex that belongs to every cycle, such as the depot
+pub const SHARED: usize = usize::MAX;
+// cycle of a vertex left out of a selective solution
+pub const UNVISITED: usize = usize::MAX - 1;
+
 impl TSPSolution {
-    pub fn new(perm_a: Vec<usize>, perm_b: Vec<usize>) -> TSPSolution {
-        let mut cycle = Vec::new();
-        let mut order = Vec::new();
-        for _ in 0..perm_a.len() + perm_b.len() {
-            cycle.push(0);
-            order.push(0);
-        }
-        for i in 0..perm_a.len() {
-            let v = perm_a[i];
-            cycle[v] = 0;
-            order[v] = i;
-        }
-        for i in 0..perm_b.len() {
-            let v = perm_b[i];
-            cycle[v] = 1;
-            order[v] = i;
-        }
-        TSPSolution {
-            perm_a,
-            perm_b,
-            cycle,
-            order,
-        }
+    pub fn new(perms: Vec<Vec<usize>>) -> TSPSolution {
+        let n = perms.iter().flatten().max().map_or(0, |max| max + 1);
+        TSPSolution::with_dimension(perms, n)
+    }
+
+    pub fn with_dimension(perms: Vec<Vec<usize>>, n: usize) -> TSPSolution {
+        let mut solution = TSPSolution {
+            perms,
+            cycle: vec![0; n],
+            order: vec![0; n],
+        };
+        solution.reorder();
+        solution
+    }
+
+    pub fn no_cycles(&self) -> usize {
+        self.perms.len()
     }
 
     pub fn reorder(&mut self) {
-        let mut i = 0;
-        for val in self.perm_a.iter() {
-            self.order[*val] = i;
-            self.cycle[*val] = 0;
-            i += 1;
+        let mut occurrences = vec![0; self.cycle.len()];
+        for val in self.perms.iter().flatten() {
+            occurrences[*val] += 1;
         }
-        let mut i = 0;
-        for val in self.perm_b.iter() {
-            self.order[*val] = i;
-            self.cycle[*val] = 1;
-            i += 1;
+        for (val, cycle) in self.cycle.iter_mut().enumerate() {
+            if occurrences[val] == 0 {
+                *cycle = UNVISITED;
+            }
+        }
+        for (c, perm) in self.perms.iter().enumerate() {
+            for (i, val) in perm.iter().enumerate() {
+                if occurrences[*val] > 1 {
+                    self.order[*val] = 0;
+                    self.cycle[*val] = SHARED;
+                } else {
+                    self.order[*val] = i;
+                    self.cycle[*val] = c;
+                }
+            }
         }
     }
 
     pub fn check(&self) -> bool {
         let mut res = true;
-        let mut i = 0;
-        for val in self.perm_a.iter() {
-            res &= (self.order[*val] == i);
-            res &= (self.cycle[*val] == 0);
-            i += 1;
-        }
-        let mut i = 0;
-        for val in self.perm_b.iter() {
-            res &= (self.order[*val] == i);
-            res &= (self.cycle[*val] == 1);
-            i += 1;
+        for (c, perm) in self.perms.iter().enumerate() {
+            for (i, val) in perm.iter().enumerate() {
+                if self.cycle[*val] == SHARED {
+                    res &= i == 0 && self.order[*val] == 0;
+                } else {
+                    res &= self.order[*val] == i;
+                    res &= self.cycle[*val] == c;
+                }
+            }
         }
         res
     }
 
+    // Whether the vertex is visited by exactly one cycle
+    pub fn is_exclusive(&self, vertex: usize) -> bool {
+        self.cycle[vertex] < UNVISITED
+    }
+
+    // For every cycle of self, the cycle of other sharing the most vertices with it
+    pub fn matching(&self, other: &TSPSolution) -> Vec<usize> {
+        let k = self.no_cycles();
+        let mut overlap = vec![vec![0; k]; k];
+        for (c, perm) in self.perms.iter().enumerate() {
+            for val in perm.iter().filter(|val| other.is_exclusive(**val)) {
+                overlap[c][other.cycle[*val]] += 1;
+            }
+        }
+        best_assignment(&overlap).1
+    }
+
     pub fn deep_clone(&self) -> Self {
         TSPSolution {
-            perm_a: self.perm_a.clone(),
-            perm_b: self.perm_b.clone(),
+            perms: self.perms.clone(),
             cycle: self.cycle.clone(),
             order: self.order.clone(),
         }
@@ -96,8 +130,7 @@ impl Solution for TSPSolution {}
 impl Clone for TSPSolution {
     fn clone(&self) -> Self {
         TSPSolution {
-            perm_a: self.perm_a.clone(),
-            perm_b: self.perm_b.clone(),
+            perms: self.perms.clone(),
             cycle: Vec::new(),
             order: Vec::new(),
         }
@@ -109,33 +142,172 @@ pub struct TSPInstance {
     pub name: String,
     pub dimension: usize,
     pub cities: Vec<City>,
-    pub distance_cache: Vec<Vec<f32>>,
+    pub edge_weight_type: EdgeWeightType,
+    pub edge_weight_format: EdgeWeightFormat,
+    pub no_cycles: usize,
+    pub balance: Balance,
+    pub capacity: Option<usize>,
+    pub demands: Vec<usize>,
+    pub depots: Vec<usize>,
+    pub depot: Option<usize>,
+    pub selection: Selection,
+    pub prizes: Vec<Cost>,
+    pub symmetric: bool,
+    pub distance: Box<dyn Distance>,
 }
 
 impl TSPInstance {
-    pub fn dist_c(&self, a: &City, b: &City) -> f32 {
-        self.distance_cache[a.id][b.id]
+    pub fn dist_c(&self, a: &City, b: &City) -> Cost {
+        self.distance.dist(a.id, b.id)
     }
 
-    pub fn dist_k(&self, a: usize, b: usize) -> f32 {
-        self.distance_cache[a][b]
+    pub fn dist_k(&self, a: usize, b: usize) -> Cost {
+        self.distance.dist(a, b)
     }
 
-    pub fn calc_dist_matrix(&mut self) {
-        for i in 0..self.dimension {
-            self.distance_cache.push(Vec::new());
-            for j in 0..self.dimension {
-                let distance = ((self.cities[i].x - self.cities[j].x).powi(2)
-                    + (self.cities[i].y - self.cities[j].y).powi(2))
-                    .sqrt()
-                    .round();
-                self.distance_cache[i].push(distance)
-            }
+    // For every vertex the given number of others closest to it, nearest first
+    pub fn nearest_neighbors(&self, count: usize) -> Vec<Vec<usize>> {
+        (0..self.dimension).map(|i| {
+            let mut others: Vec<usize> = (0..self.dimension).filter(|j| *j != i).collect();
+            others.sort_by_key(|j| self.dist_k(i, *j));
+            others.truncate(count);
+            others
+        }).collect()
+    }
+
+    // Explicit weights cannot be computed on the fly, such instances keep their matrix
+    pub fn use_distance(&mut self, kind: DistanceKind) -> Result<(), String> {
+        if kind == DistanceKind::OnTheFly && self.edge_weight_type == EdgeWeightType::Explicit {
+            return Err(String::from("explicit weights cannot be computed on the fly"));
+        }
+        let n = self.dimension;
+        self.distance = match kind {
+            DistanceKind::Dense => Box::new(DenseDistance::from_fn(n, |i, j| self.dist_k(i, j))),
+            DistanceKind::Compact => Box::new(CompactDistance::from_fn(n, self.symmetric, |i, j| self.dist_k(i, j))),
+            DistanceKind::OnTheFly => Box::new(OnTheFlyDistance::new(self.edge_weight_type, self.cities.clone())),
+        };
+        Ok(())
+    }
+
+    pub fn check_balance(&self) -> Result<(), String> {
+        let size = self.selection.size(self.dimension);
+        let spread = if self.depot.is_some() { size.saturating_sub(1) } else { size };
+        self.balance.check(spread, self.no_cycles)
+    }
+
+    // Cycle sizes construction aims at, in depot mode every cycle holds the depot on top of its share
+    pub fn targets(&self) -> Vec<usize> {
+        let size = self.selection.size(self.dimension);
+        match self.depot {
+            Some(_) => self.balance.targets(size - 1, self.no_cycles).into_iter().map(|t| t + 1).collect(),
+            None => self.balance.targets(size, self.no_cycles),
+        }
+    }
+
+    pub fn prize(&self, vertex: usize) -> Cost {
+        if self.prizes.is_empty() {
+            0
+        } else {
+            self.prizes[vertex]
+        }
+    }
+
+    pub fn visited(&self, solution: &TSPSolution) -> usize {
+        let shared = self.depot.map_or(0, |_| solution.no_cycles().saturating_sub(1));
+        solution.perms.iter().map(|perm| perm.len()).sum::<usize>() - shared
+    }
+
+    pub fn profit(&self, solution: &TSPSolution) -> Cost {
+        if self.prizes.is_empty() {
+            return 0;
+        }
+        let shared = self.depot.map_or(0, |depot| self.prize(depot) * solution.no_cycles().saturating_sub(1) as Cost);
+        solution.perms.iter().flatten().map(|vertex| self.prize(*vertex)).sum::<Cost>() - shared
+    }
+
+    pub fn demand(&self, vertex: usize) -> usize {
+        if self.demands.is_empty() {
+            0
+        } else {
+            self.demands[vertex]
+        }
+    }
+
+    pub fn load(&self, perm: &[usize]) -> usize {
+        if self.demands.is_empty() {
+            return 0;
         }
+        perm.iter().map(|vertex| self.demands[*vertex]).sum()
+    }
+
+    pub fn fits(&self, load: usize) -> bool {
+        self.capacity.is_none_or(|capacity| load <= capacity)
+    }
+
+    pub fn is_feasible(&self, solution: &TSPSolution) -> bool {
+        solution.perms.iter().all(|perm| self.fits(self.load(perm)))
     }
 
-    fn eval_permutation(&self, perm: &Vec<usize>) -> f32 {
-        let mut acc: f32 = 0.;
+    pub fn overload(&self, load: usize) -> Cost {
+        self.capacity.map_or(0, |capacity| load.saturating_sub(capacity) as Cost)
+    }
+
+    // Change of the overload penalty of eval when cycles go from the first load of each pair to the second one
+    pub fn overload_change(&self, loads: &[(usize, usize)]) -> Cost {
+        OVERLOAD_PENALTY * loads.iter().map(|(before, after)| self.overload(*after) - self.overload(*before)).sum::<Cost>()
+    }
+
+    // Demand cannot be split between cycles and every cycle of a depot instance carries the depot demand
+    pub fn check_capacity(&self) -> Result<(), String> {
+        let capacity = match self.capacity {
+            Some(capacity) => capacity,
+            None => return Ok(()),
+        };
+        let depot = self.depot.map_or(0, |depot| self.demand(depot));
+        let vertices = (0..self.dimension).filter(|vertex| Some(*vertex) != self.depot);
+        if let Some(vertex) = vertices.clone().find(|vertex| depot + self.demand(*vertex) > capacity) {
+            return Err(format!("demand {} of vertex {} exceeds capacity {}", self.demand(vertex), vertex + 1, capacity));
+        }
+        let total: usize = vertices.map(|vertex| self.demand(vertex)).sum::<usize>() + depot * self.no_cycles;
+        if !self.selection.is_selective() && total > capacity * self.no_cycles {
+            return Err(format!("total demand {} exceeds capacity {} of {} cycles", total, capacity, self.no_cycles));
+        }
+        Ok(())
+    }
+
+    // Whether exchanging vertices a and b between their cycles keeps both of them within capacity
+    pub fn allows_swap(&self, solution: &TSPSolution, a: usize, b: usize) -> bool {
+        let (cycle_a, cycle_b) = (solution.cycle[a], solution.cycle[b]);
+        if self.capacity.is_none() || cycle_a == cycle_b {
+            return true;
+        }
+        let (demand_a, demand_b) = (self.demand(a), self.demand(b));
+        self.fits(self.load(&solution.perms[cycle_a]) - demand_a + demand_b)
+            && self.fits(self.load(&solution.perms[cycle_b]) - demand_b + demand_a)
+    }
+
+    pub fn swap_overload_change(&self, solution: &TSPSolution, a: usize, b: usize) -> Cost {
+        let (cycle_a, cycle_b) = (solution.cycle[a], solution.cycle[b]);
+        if self.capacity.is_none() || cycle_a == cycle_b {
+            return 0;
+        }
+        let (demand_a, demand_b) = (self.demand(a), self.demand(b));
+        let (load_a, load_b) = (self.load(&solution.perms[cycle_a]), self.load(&solution.perms[cycle_b]));
+        self.overload_change(&[(load_a, load_a - demand_a + demand_b), (load_b, load_b - demand_b + demand_a)])
+    }
+
+    pub fn relocation_overload_change(&self, solution: &TSPSolution, vertex: usize, cycle: usize) -> Cost {
+        let from = solution.cycle[vertex];
+        if self.capacity.is_none() || cycle == from {
+            return 0;
+        }
+        let demand = self.demand(vertex);
+        let (load_from, load_to) = (self.load(&solution.perms[from]), self.load(&solution.perms[cycle]));
+        self.overload_change(&[(load_from, load_from - demand), (load_to, load_to + demand)])
+    }
+
+    fn eval_permutation(&self, perm: &Vec<usize>) -> Cost {
+        let mut acc: Cost = 0;
         for i in 0..perm.len() {
             acc += self.dist_k(perm[i], perm[(i + 1) % perm.len()]);
         }
@@ -144,62 +316,181 @@ impl TSPInstance {
 }
 
 impl Instance<TSPSolution> for TSPInstance {
-    fn eval(&self, solution: &TSPSolution) -> f32 {
-        if self.dimension != (solution.perm_a.len() + solution.perm_b.len()) {
+    fn eval(&self, solution: &TSPSolution) -> Cost {
+        if !self.selection.is_selective() && self.dimension != self.visited(solution) {
             panic!("Solution of inadequate size was given")
         }
-        self.eval_permutation(&solution.perm_a) +
-            self.eval_permutation(&solution.perm_b)
+        let length: Cost = solution.perms.iter().map(|perm| self.eval_permutation(perm)).sum();
+        let overload: Cost = solution.perms.iter().map(|perm| self.overload(self.load(perm))).sum();
+        let length = length + OVERLOAD_PENALTY * overload;
+        if self.selection.is_selective() {
+            length - self.profit(solution)
+        } else {
+            length
+        }
     }
 
-    fn parse_file(file_name: &str) -> Self {
-        let cts = contents(file_name);
-        let mut instance = TSPInstance::default();
-        let mut read_points = false;
-        let mut i = 0;
+    fn parse_file(file_name: &str) -> Result<Self, ParseError> {
+        let cts = fs::read_to_string(file_name)
+            .map_err(|err| ParseError::new(file_name, None, ParseErrorKind::Io(err)))?;
+        let mut instance = TSPInstance {
+            no_cycles: 2,
+            ..Default::default()
+        };
+        let mut dimension: Option<usize> = None;
+        let mut section = "";
+        let mut weights: Vec<Cost> = Vec::new();
+        let mut last_line = 0;
 
-        for line in cts.lines() {
-            let line: String = line.trim().parse().unwrap();
-            if line == "EOF" {
+        for (line_i, line) in cts.lines().enumerate() {
+            let line_no = line_i + 1;
+            last_line = line_no;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line == EOF {
                 break;
             }
-            if !read_points {
-                let split: Vec<&str> = line.split(":").collect::<Vec<&str>>();
-                let (k, v) = if split.len() > 1 {
-                    (split[0].trim(), split[1].trim())
-                } else {
-                    (split[0].trim(), split[0].trim())
-                };
-                match k {
-                    NAME => {
-                        instance.name = String::from(v);
+            let split: Vec<&str> = line.splitn(2, ':').collect::<Vec<&str>>();
+            let (k, v) = if split.len() > 1 {
+                (split[0].trim(), split[1].trim())
+            } else {
+                (split[0].trim(), split[0].trim())
+            };
+            match k {
+                NAME => {
+                    instance.name = String::from(v);
+                }
+                DIMENSION => {
+                    // an empty instance has no tour, explicit triangles of it would not even have a size
+                    let n: usize = parse_number(v, file_name, line_no)?;
+                    if n == 0 {
+                        return Err(ParseError::at(file_name, line_no, ParseErrorKind::Unsupported(DIMENSION, String::from(v))));
+                    }
+                    dimension = Some(n);
+                }
+                EDGE_WEIGHT_TYPE => {
+                    instance.edge_weight_type = EdgeWeightType::parse(v).ok_or_else(|| ParseError::at(
+                        file_name, line_no, ParseErrorKind::Unsupported(EDGE_WEIGHT_TYPE, String::from(v))))?;
+                }
+                CAPACITY => {
+                    instance.capacity = Some(parse_number(v, file_name, line_no)?);
+                }
+                EDGE_WEIGHT_FORMAT => {
+                    instance.edge_weight_format = EdgeWeightFormat::parse(v).ok_or_else(|| ParseError::at(
+                        file_name, line_no, ParseErrorKind::Unsupported(EDGE_WEIGHT_FORMAT, String::from(v))))?;
+                }
+                NODE_COORD_SECTION | EDGE_WEIGHT_SECTION | DISPLAY_DATA_SECTION | DEMAND_SECTION | DEPOT_SECTION | PRIZE_SECTION => {
+                    if dimension.is_none() {
+                        return Err(ParseError::at(file_name, line_no, ParseErrorKind::MissingKey(DIMENSION)));
                     }
-                    DIMENSION => {
-                        instance.dimension = String::from(v).parse().unwrap();
+                    if k == DEMAND_SECTION {
+                        instance.demands = vec![0; dimension.unwrap()];
                     }
-                    NODE_COORD_SECTION => {
-                        read_points = true;
+                    if k == PRIZE_SECTION {
+                        instance.prizes = vec![0; dimension.unwrap()];
                     }
-                    _ => ()
+                    section = k;
                 }
-            } else {
-                let split: Vec<&str> = line.split(" ").collect::<Vec<&str>>();
-                let city = City {
-                    id: i,
-                    label: String::from(split[0]),
-                    x: split[1].parse::<f32>().unwrap(),
-                    y: split[2].parse::<f32>().unwrap(),
-                };
-                i += 1;
-                instance.cities.push(city)
+                // sections such as FIXED_EDGES_SECTION or EDGE_DATA_SECTION are skipped
+                _ if k.ends_with("_SECTION") => {
+                    section = "";
+                }
+                _ if section == NODE_COORD_SECTION || section == DISPLAY_DATA_SECTION => {
+                    let split: Vec<&str> = line.split_whitespace().collect::<Vec<&str>>();
+                    if split.len() < 3 {
+                        return Err(ParseError::at(file_name, line_no,
+                            ParseErrorKind::MissingValue(String::from("node coordinates"))));
+                    }
+                    let city = City {
+                        id: instance.cities.len(),
+                        label: String::from(split[0]),
+                        x: parse_number(split[1], file_name, line_no)?,
+                        y: parse_number(split[2], file_name, line_no)?,
+                        z: if split.len() > 3 { parse_number(split[3], file_name, line_no)? } else { 0.0 },
+                    };
+                    instance.cities.push(city)
+                }
+                _ if section == DEMAND_SECTION => {
+                    let split: Vec<&str> = line.split_whitespace().collect::<Vec<&str>>();
+                    if split.len() < 2 {
+                        return Err(ParseError::at(file_name, line_no,
+                            ParseErrorKind::MissingValue(String::from("demand"))));
+                    }
+                    let node = parse_node(split[0], dimension.unwrap(), file_name, line_no)?;
+                    instance.demands[node] = parse_number(split[1], file_name, line_no)?;
+                }
+                _ if section == PRIZE_SECTION => {
+                    let split: Vec<&str> = line.split_whitespace().collect::<Vec<&str>>();
+                    if split.len() < 2 {
+                        return Err(ParseError::at(file_name, line_no,
+                            ParseErrorKind::MissingValue(String::from("prize"))));
+                    }
+                    let node = parse_node(split[0], dimension.unwrap(), file_name, line_no)?;
+                    instance.prizes[node] = parse_number(split[1], file_name, line_no)?;
+                }
+                _ if section == DEPOT_SECTION => {
+                    for token in line.split_whitespace() {
+                        if token == "-1" {
+                            section = "";
+                            break;
+                        }
+                        instance.depots.push(parse_node(token, dimension.unwrap(), file_name, line_no)?);
+                    }
+                }
+                _ if section == EDGE_WEIGHT_SECTION => {
+                    for weight in line.split_whitespace() {
+                        weights.push(parse_number(weight, file_name, line_no)?);
+                    }
+                }
+                _ => ()
+            }
+        }
+
+        instance.dimension = dimension.ok_or_else(|| ParseError::new(
+            file_name, None, ParseErrorKind::MissingKey(DIMENSION)))?;
+        if instance.edge_weight_type == EdgeWeightType::Explicit {
+            let expected = instance.edge_weight_format.size(instance.dimension);
+            if instance.edge_weight_format == EdgeWeightFormat::Function || weights.len() != expected {
+                return Err(ParseError::at(file_name, last_line,
+                    ParseErrorKind::WrongCount("edge weights", expected, weights.len())));
+            }
+            instance.distance = Box::new(DenseDistance::new(
+                instance.edge_weight_format.to_matrix(instance.dimension, &weights)));
+            instance.symmetric = (0..instance.dimension)
+                .all(|i| (0..i).all(|j| instance.dist_k(i, j) == instance.dist_k(j, i)));
+        } else {
+            if instance.cities.len() != instance.dimension {
+                return Err(ParseError::at(file_name, last_line,
+                    ParseErrorKind::WrongCount("nodes", instance.dimension, instance.cities.len())));
+            }
+            instance.symmetric = true;
+            instance.distance = Box::new(OnTheFlyDistance::new(instance.edge_weight_type, instance.cities.clone()));
+            let kind = DistanceKind::auto(instance.dimension, false);
+            if kind != DistanceKind::OnTheFly {
+                instance.use_distance(kind).unwrap();
             }
         }
+        Ok(instance)
+    }
+}
+
+pub(crate) fn parse_number<T: FromStr>(token: &str, file_name: &str, line: usize) -> Result<T, ParseError> {
+    token.parse::<T>()
+        .map_err(|_| ParseError::at(file_name, line, ParseErrorKind::InvalidNumber(String::from(token))))
+}
 
-        instance.calc_dist_matrix();
-        instance
+// TSPLIB node ids are 1-based
+pub(crate) fn parse_node(token: &str, dimension: usize, file_name: &str, line: usize) -> Result<usize, ParseError> {
+    let node: usize = parse_number(token, file_name, line)?;
+    if node == 0 || node > dimension {
+        return Err(ParseError::at(file_name, line, ParseErrorKind::InvalidNode(String::from(token))));
     }
+    Ok(node - 1)
 }
 
 def_str_consts! {
-    NAME, DIMENSION, NODE_COORD_SECTION
+    NAME, DIMENSION, EDGE_WEIGHT_TYPE, EDGE_WEIGHT_FORMAT, CAPACITY,
+    NODE_COORD_SECTION, EDGE_WEIGHT_SECTION, DISPLAY_DATA_SECTION, DEMAND_SECTION, DEPOT_SECTION, PRIZE_SECTION, EOF
 }
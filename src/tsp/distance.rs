@@ -0,0 +1,45 @@
+use crate::traits::Cost;
+use std::fmt::Debug;
+use crate::tsp::distances::dense_distance::DenseDistance;
+
+pub trait Distance: Debug + Send + Sync {
+    fn dist(&self, a: usize, b: usize) -> Cost;
+}
+
+impl Default for Box<dyn Distance> {
+    fn default() -> Self {
+        Box::new(DenseDistance::new(Vec::new()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DistanceKind {
+    Dense,
+    OnTheFly,
+    Compact,
+}
+
+// Largest dimensions kept in a dense and in a compact matrix, about 64 MB and 512 MB
+const DENSE_LIMIT: usize = 4000;
+const COMPACT_LIMIT: usize = 16000;
+
+impl DistanceKind {
+    pub fn parse(value: &str) -> Option<DistanceKind> {
+        match value {
+            "Dense" => Some(DistanceKind::Dense),
+            "OnTheFly" => Some(DistanceKind::OnTheFly),
+            "Compact" => Some(DistanceKind::Compact),
+            _ => None
+        }
+    }
+
+    pub fn auto(dimension: usize, explicit: bool) -> DistanceKind {
+        if explicit || dimension <= DENSE_LIMIT {
+            DistanceKind::Dense
+        } else if dimension <= COMPACT_LIMIT {
+            DistanceKind::Compact
+        } else {
+            DistanceKind::OnTheFly
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::traits::Cost;
+use std::convert::TryFrom;
+use crate::tsp::distance::Distance;
+
+// Integer weights in one flat vector, only the lower triangle is kept for symmetric instances
+#[derive(Debug)]
+pub struct CompactDistance {
+    n: usize,
+    symmetric: bool,
+    weights: Vec<u32>,
+}
+
+impl CompactDistance {
+    pub fn from_fn(n: usize, symmetric: bool, dist: impl Fn(usize, usize) -> Cost) -> CompactDistance {
+        let mut weights = Vec::with_capacity(if symmetric { n * (n + 1) / 2 } else { n * n });
+        for i in 0..n {
+            for j in 0..if symmetric { i + 1 } else { n } {
+                let weight = dist(i, j);
+                weights.push(u32::try_from(weight)
+                    .unwrap_or_else(|_| panic!("Compact distances need weights within u32, got {}", weight)));
+            }
+        }
+        CompactDistance {
+            n,
+            symmetric,
+            weights
+        }
+    }
+
+    fn index(&self, a: usize, b: usize) -> usize {
+        if !self.symmetric {
+            a * self.n + b
+        } else if a >= b {
+            a * (a + 1) / 2 + b
+        } else {
+            b * (b + 1) / 2 + a
+        }
+    }
+}
+
+impl Distance for CompactDistance {
+    fn dist(&self, a: usize, b: usize) -> Cost {
+        self.weights[self.index(a, b)] as Cost
+    }
+}
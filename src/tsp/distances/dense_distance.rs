@@ -0,0 +1,25 @@
+use crate::traits::Cost;
+use crate::tsp::distance::Distance;
+
+#[derive(Debug)]
+pub struct DenseDistance {
+    matrix: Vec<Vec<Cost>>,
+}
+
+impl DenseDistance {
+    pub fn new(matrix: Vec<Vec<Cost>>) -> DenseDistance {
+        DenseDistance {
+            matrix
+        }
+    }
+
+    pub fn from_fn(n: usize, dist: impl Fn(usize, usize) -> Cost) -> DenseDistance {
+        DenseDistance::new((0..n).map(|i| (0..n).map(|j| dist(i, j)).collect()).collect())
+    }
+}
+
+impl Distance for DenseDistance {
+    fn dist(&self, a: usize, b: usize) -> Cost {
+        self.matrix[a][b]
+    }
+}
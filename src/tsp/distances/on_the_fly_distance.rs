@@ -0,0 +1,30 @@
+use crate::traits::Cost;
+use crate::tsp::distance::Distance;
+use crate::tsp::def::City;
+use crate::tsp::edge_weight::EdgeWeightType;
+
+#[derive(Debug)]
+pub struct OnTheFlyDistance {
+    edge_weight_type: EdgeWeightType,
+    cities: Vec<City>,
+}
+
+impl OnTheFlyDistance {
+    pub fn new(edge_weight_type: EdgeWeightType, cities: Vec<City>) -> OnTheFlyDistance {
+        assert_ne!(edge_weight_type, EdgeWeightType::Explicit, "Explicit weights cannot be computed on the fly");
+        OnTheFlyDistance {
+            edge_weight_type,
+            cities
+        }
+    }
+}
+
+impl Distance for OnTheFlyDistance {
+    fn dist(&self, a: usize, b: usize) -> Cost {
+        if a == b {
+            0
+        } else {
+            self.edge_weight_type.dist(&self.cities[a], &self.cities[b])
+        }
+    }
+}
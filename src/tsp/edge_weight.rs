@@ -0,0 +1,183 @@
+use crate::tsp::def::City;
+use crate::traits::Cost;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum EdgeWeightType {
+    Explicit,
+    #[default]
+    Euc2d,
+    Euc3d,
+    Max2d,
+    Max3d,
+    Man2d,
+    Man3d,
+    Ceil2d,
+    Geo,
+    Att,
+}
+
+impl EdgeWeightType {
+    pub fn parse(value: &str) -> Option<EdgeWeightType> {
+        match value {
+            "EXPLICIT" => Some(EdgeWeightType::Explicit),
+            "EUC_2D" => Some(EdgeWeightType::Euc2d),
+            "EUC_3D" => Some(EdgeWeightType::Euc3d),
+            "MAX_2D" => Some(EdgeWeightType::Max2d),
+            "MAX_3D" => Some(EdgeWeightType::Max3d),
+            "MAN_2D" => Some(EdgeWeightType::Man2d),
+            "MAN_3D" => Some(EdgeWeightType::Man3d),
+            "CEIL_2D" => Some(EdgeWeightType::Ceil2d),
+            "GEO" => Some(EdgeWeightType::Geo),
+            "ATT" => Some(EdgeWeightType::Att),
+            _ => None
+        }
+    }
+
+    pub fn dist(&self, a: &City, b: &City) -> Cost {
+        let (dx, dy, dz) = (a.x - b.x, a.y - b.y, a.z - b.z);
+        let distance = match self {
+            EdgeWeightType::Euc2d => nint((dx * dx + dy * dy).sqrt()),
+            EdgeWeightType::Euc3d => nint((dx * dx + dy * dy + dz * dz).sqrt()),
+            EdgeWeightType::Max2d => nint(dx.abs()).max(nint(dy.abs())),
+            EdgeWeightType::Max3d => nint(dx.abs()).max(nint(dy.abs())).max(nint(dz.abs())),
+            EdgeWeightType::Man2d => nint(dx.abs() + dy.abs()),
+            EdgeWeightType::Man3d => nint(dx.abs() + dy.abs() + dz.abs()),
+            EdgeWeightType::Ceil2d => (dx * dx + dy * dy).sqrt().ceil(),
+            EdgeWeightType::Geo => geo_dist(a, b),
+            EdgeWeightType::Att => att_dist(dx, dy),
+            EdgeWeightType::Explicit => panic!("Explicit weights cannot be computed from coordinates"),
+        };
+        distance as Cost
+    }
+}
+
+fn nint(x: f64) -> f64 {
+    (x + 0.5).floor()
+}
+
+// TSPLIB uses a truncated value of pi for geographical distances
+#[allow(clippy::approx_constant)]
+const GEO_PI: f64 = 3.141592;
+const GEO_RADIUS: f64 = 6378.388;
+
+fn geo_radians(x: f64) -> f64 {
+    let deg = x.trunc();
+    let min = x - deg;
+    GEO_PI * (deg + 5.0 * min / 3.0) / 180.0
+}
+
+fn geo_dist(a: &City, b: &City) -> f64 {
+    let (lat_a, lon_a) = (geo_radians(a.x), geo_radians(a.y));
+    let (lat_b, lon_b) = (geo_radians(b.x), geo_radians(b.y));
+    let q1 = (lon_a - lon_b).cos();
+    let q2 = (lat_a - lat_b).cos();
+    let q3 = (lat_a + lat_b).cos();
+    (GEO_RADIUS * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos() + 1.0).trunc()
+}
+
+fn att_dist(dx: f64, dy: f64) -> f64 {
+    let r = ((dx * dx + dy * dy) / 10.0).sqrt();
+    let t = nint(r);
+    if t < r {
+        t + 1.0
+    } else {
+        t
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum EdgeWeightFormat {
+    #[default]
+    Function,
+    FullMatrix,
+    UpperRow,
+    LowerRow,
+    UpperDiagRow,
+    LowerDiagRow,
+    UpperCol,
+    LowerCol,
+    UpperDiagCol,
+    LowerDiagCol,
+}
+
+impl EdgeWeightFormat {
+    pub fn parse(value: &str) -> Option<EdgeWeightFormat> {
+        match value {
+            "FUNCTION" => Some(EdgeWeightFormat::Function),
+            "FULL_MATRIX" => Some(EdgeWeightFormat::FullMatrix),
+            "UPPER_ROW" => Some(EdgeWeightFormat::UpperRow),
+            "LOWER_ROW" => Some(EdgeWeightFormat::LowerRow),
+            "UPPER_DIAG_ROW" => Some(EdgeWeightFormat::UpperDiagRow),
+            "LOWER_DIAG_ROW" => Some(EdgeWeightFormat::LowerDiagRow),
+            "UPPER_COL" => Some(EdgeWeightFormat::UpperCol),
+            "LOWER_COL" => Some(EdgeWeightFormat::LowerCol),
+            "UPPER_DIAG_COL" => Some(EdgeWeightFormat::UpperDiagCol),
+            "LOWER_DIAG_COL" => Some(EdgeWeightFormat::LowerDiagCol),
+            _ => None
+        }
+    }
+
+    pub fn size(&self, n: usize) -> usize {
+        match self {
+            EdgeWeightFormat::Function => 0,
+            EdgeWeightFormat::FullMatrix => n * n,
+            EdgeWeightFormat::UpperRow | EdgeWeightFormat::LowerRow
+            | EdgeWeightFormat::UpperCol | EdgeWeightFormat::LowerCol => n * n.saturating_sub(1) / 2,
+            _ => n * (n + 1) / 2,
+        }
+    }
+
+    // Column-wise triangles list the same (i, j) pairs as the opposite row-wise triangles,
+    // so for symmetric matrices they are read as their row counterparts
+    pub fn to_matrix(&self, n: usize, weights: &[Cost]) -> Vec<Vec<Cost>> {
+        let mut matrix = vec![vec![0; n]; n];
+        let mut it = weights.iter();
+        let mut set = |i: usize, j: usize, matrix: &mut Vec<Vec<Cost>>| {
+            let w = *it.next().unwrap();
+            matrix[i][j] = w;
+            matrix[j][i] = w;
+        };
+        match self {
+            // ATSP files often put a large value on the diagonal instead of 0
+            EdgeWeightFormat::FullMatrix => {
+                for i in 0..n {
+                    for j in 0..n {
+                        if i != j {
+                            matrix[i][j] = weights[i * n + j];
+                        }
+                    }
+                }
+            }
+            EdgeWeightFormat::UpperRow | EdgeWeightFormat::LowerCol => {
+                for i in 0..n {
+                    for j in i + 1..n {
+                        set(i, j, &mut matrix);
+                    }
+                }
+            }
+            EdgeWeightFormat::LowerRow | EdgeWeightFormat::UpperCol => {
+                for i in 0..n {
+                    for j in 0..i {
+                        set(i, j, &mut matrix);
+                    }
+                }
+            }
+            EdgeWeightFormat::UpperDiagRow | EdgeWeightFormat::LowerDiagCol => {
+                for i in 0..n {
+                    for j in i..n {
+                        set(i, j, &mut matrix);
+                    }
+                }
+            }
+            EdgeWeightFormat::LowerDiagRow | EdgeWeightFormat::UpperDiagCol => {
+                for i in 0..n {
+                    for j in 0..=i {
+                        set(i, j, &mut matrix);
+                    }
+                }
+            }
+            EdgeWeightFormat::Function => panic!("FUNCTION format has no explicit weights"),
+        }
+        matrix
+    }
+}
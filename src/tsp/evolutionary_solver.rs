@@ -1,22 +1,19 @@
-use std::time::Instant;
-
-use rand::{Rng, thread_rng};
+use rand::Rng;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 
-use crate::traits::{Instance, Solver};
+use crate::traits::{Instance, Solver, Cost, Observer, NoObserver};
 use crate::tsp::def::{TSPInstance, TSPSolution};
 use crate::tsp::neighborhoods::neighborhood::Neighborhood;
-use crate::tsp::neighborhoods::transition::Transition;
-use crate::tsp::solver::GreedySolver;
-use crate::tsp::pickers::regret_picker::RegretPicker;
-use std::collections::{BinaryHeap, BTreeSet, HashMap};
-use std::collections::HashSet;
-use std::cmp::{Ordering, max};
-use crate::utils::{random_combination, MinFloat};
+use crate::tsp::neighborhoods::transition_kind::TransitionKind;
+use std::collections::{BinaryHeap, HashMap};
+use std::cmp::Ordering;
+use crate::utils::{random_combination, MinCost};
+use crate::tsp::termination::{Termination, Progress};
 
 pub struct Candidate {
     solution: TSPSolution,
-    distance: f32,
+    distance: Cost,
     inbred_count: usize,
     cross_count: usize,
 }
@@ -33,8 +30,7 @@ impl Ord for Candidate {
     fn cmp(&self, other: &Self) -> Ordering {
         other
             .distance
-            .partial_cmp(&self.distance)
-            .unwrap()
+            .cmp(&self.distance)
     }
 }
 
@@ -47,8 +43,8 @@ impl PartialOrd for Candidate {
 
 pub struct Population {
     pop_registry: Vec<Candidate>,
-    pop_queue: BinaryHeap<(MinFloat, usize)>,
-    score_map: HashMap<usize, usize>
+    pop_queue: BinaryHeap<(MinCost, usize)>,
+    score_map: HashMap<Cost, usize>
 }
 
 impl Population {
@@ -66,13 +62,13 @@ impl Population {
 
     pub fn register(&mut self, candidate: Candidate) {
         let position = self.pop_registry.len();
-        self.score_map.insert(candidate.distance.clone() as usize, position);
-        self.pop_queue.push((MinFloat(candidate.distance.clone()), position));
+        self.score_map.insert(candidate.distance, position);
+        self.pop_queue.push((MinCost(candidate.distance), position));
         self.pop_registry.push(candidate);
     }
 
     pub fn inbred(&mut self, candidate: &Candidate) {
-        let index = self.score_map.get(&(candidate.distance as usize)).unwrap();
+        let index = self.score_map.get(&candidate.distance).unwrap();
         self.pop_registry[*index].inbred_count += 1;
         self.pop_registry[*index].cross_count += 1;
     }
@@ -84,16 +80,16 @@ impl Population {
     pub fn replace(&mut self, candidate: Candidate) {
         let popped_candidate = self.pop_queue.pop().unwrap();
         self.pop_registry[popped_candidate.1] = candidate;
-        self.pop_queue.push((MinFloat(self.pop_registry[popped_candidate.1].distance.clone()), popped_candidate.1));
-        self.score_map.insert(self.pop_registry[popped_candidate.1].distance.clone() as usize, popped_candidate.1);
+        self.pop_queue.push((MinCost(self.pop_registry[popped_candidate.1].distance), popped_candidate.1));
+        self.score_map.insert(self.pop_registry[popped_candidate.1].distance, popped_candidate.1);
     }
 
-    pub fn threshold(&self) -> f32 {
+    pub fn threshold(&self) -> Cost {
         self.pop_queue.peek().unwrap().0.0
     }
 
     pub fn has(&self, candidate: &Candidate) -> bool {
-        self.score_map.contains_key(&(candidate.distance as usize))
+        self.score_map.contains_key(&candidate.distance)
     }
 
     pub fn get(&self, index: usize) -> &Candidate {
@@ -112,8 +108,8 @@ impl Population {
         self.pop_queue.clear();
         self.score_map.clear();
         for i in 0..self.size() {
-            self.pop_queue.push((MinFloat(self.pop_registry[i].distance), i));
-            self.score_map.insert(self.pop_registry[i].distance as usize, i);
+            self.pop_queue.push((MinCost(self.pop_registry[i].distance), i));
+            self.score_map.insert(self.pop_registry[i].distance, i);
         }
     }
 }
@@ -121,94 +117,66 @@ impl Population {
 pub struct EvolutionarySolver {
     local_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
     construction_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
-    time: f32,
+    termination: Termination,
     population_size: usize,
     steps_to_mutation: usize,
-    transition: fn() -> Vec<Box<dyn Transition>>,
+    transition: Vec<TransitionKind>,
 }
 
 impl EvolutionarySolver {
     pub fn new(local_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
                construction_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
-               time: f32,
+               termination: Termination,
                population_size: usize,
                steps_to_mutation: usize,
-               transition: fn() -> Vec<Box<dyn Transition>>) -> EvolutionarySolver {
+               transition: Vec<TransitionKind>) -> EvolutionarySolver {
         EvolutionarySolver {
             local_solver,
             construction_solver,
-            time,
+            termination,
             population_size,
             steps_to_mutation,
             transition,
         }
     }
 
-    fn cross_over(&self, parents: (usize, usize), instance: &TSPInstance, population: &mut Population) -> Option<Candidate> {
+    fn cross_over(&self, parents: (usize, usize), instance: &TSPInstance, population: &mut Population, progress: &mut Progress, rng: &mut StdRng) -> Option<Candidate> {
         let candidate_a = population.get(parents.0);
         let candidate_b = population.get(parents.1);
-        let mut new_perm_a = vec![];
-        let mut new_perm_b = vec![];
-        let mut noswap_count = 0;
-
-        for vertex_i in 0..instance.dimension {
-            if candidate_a.solution.cycle[vertex_i] == candidate_b.solution.cycle[vertex_i] {
-                noswap_count += 1;
-            }
-        }
-        let mut a_cycle_dest = 0;
-        let mut a_perm_a = &candidate_a.solution.perm_a;
-        let mut a_perm_b = &candidate_a.solution.perm_b;
-
-        let b_perm_a = &candidate_b.solution.perm_a;
-        let b_perm_b = &candidate_b.solution.perm_b;
+        let mapping = candidate_a.solution.matching(&candidate_b.solution);
         let b_order = &candidate_b.solution.order;
         let b_cycle = &candidate_b.solution.cycle;
+        let mut new_perms = vec![];
+
+        for (a_cycle, a_perm) in candidate_a.solution.perms.iter().enumerate() {
+            let b_cycle_dest = mapping[a_cycle];
+            let b_perm = &candidate_b.solution.perms[b_cycle_dest];
+            let mut new_perm = vec![];
+            for a_vert_i in 0..a_perm.len() {
+                let g_vert = a_perm[a_vert_i];
+                if b_cycle[g_vert] == b_cycle_dest
+                    && (b_perm[(b_order[g_vert] + 1) % b_perm.len()] == a_perm[(a_vert_i + 1) % a_perm.len()]
+                    || b_perm[(b_order[g_vert] + b_perm.len() - 1) % b_perm.len()] == a_perm[(a_vert_i + 1) % a_perm.len()]) {
+                    let a = g_vert;
+                    if new_perm.is_empty() || a != *new_perm.last().unwrap() {
+                        new_perm.push(g_vert);
+                    }
 
-        if noswap_count < instance.dimension / 2 {
-            a_perm_a = &candidate_a.solution.perm_b;
-            a_perm_b = &candidate_a.solution.perm_a;
-            a_cycle_dest = 1;
-        }
-        for a_vert_i in 0..a_perm_a.len() {
-            let g_vert = a_perm_a[a_vert_i];
-            if b_cycle[g_vert] == a_cycle_dest
-                && (b_perm_a[(b_order[g_vert] + 1) % a_perm_a.len()] == a_perm_a[(a_vert_i + 1) % a_perm_a.len()]
-                || b_perm_a[(b_order[g_vert] + a_perm_a.len() - 1) % a_perm_a.len()] == a_perm_a[(a_vert_i + 1) % a_perm_a.len()]) {
-                let a = g_vert;
-                if new_perm_a.is_empty() || a != *new_perm_a.last().unwrap() {
-                    new_perm_a.push(g_vert);
-                }
-
-                let a = a_perm_a[(a_vert_i + 1) % a_perm_a.len()];
-                if new_perm_a.is_empty() || a != *new_perm_a.first().unwrap() {
-                    new_perm_a.push(a);
-                }
-            }
-        }
-
-        for a_vert_i in 0..a_perm_b.len() {
-            let g_vert = a_perm_b[a_vert_i];
-            if b_cycle[g_vert] == 1 - a_cycle_dest
-                && (b_perm_b[(b_order[g_vert] + 1) % b_perm_b.len()] == a_perm_b[(a_vert_i + 1) % a_perm_b.len()]
-                || b_perm_b[(b_order[g_vert] + b_perm_b.len() - 1) % b_perm_b.len()] == a_perm_b[(a_vert_i + 1) % a_perm_b.len()]) {
-                let a = g_vert;
-                if new_perm_b.is_empty() || a != *new_perm_b.last().unwrap() {
-                    new_perm_b.push(g_vert);
-                }
-
-                let a = a_perm_b[(a_vert_i + 1) % a_perm_b.len()];
-                if new_perm_b.is_empty() || a != *new_perm_b.first().unwrap() {
-                    new_perm_b.push(a);
+                    let a = a_perm[(a_vert_i + 1) % a_perm.len()];
+                    if new_perm.is_empty() || a != *new_perm.first().unwrap() {
+                        new_perm.push(a);
+                    }
                 }
             }
+            new_perms.push(new_perm);
         }
 
-        let mut solution = TSPSolution{ perm_a: new_perm_a, perm_b: new_perm_b, cycle: vec![], order: vec![] };
-        let mut solution = self.construction_solver.solve_s(0, instance, solution);
+        let solution = TSPSolution { perms: new_perms, cycle: vec![], order: vec![] };
+        let mut solution = self.construction_solver.solve_s(0, instance, solution, rng);
         solution.reorder();
-        let mut solution = self.local_solver.solve_s(0, instance, solution);
+        let solution = self.local_solver.solve_s(0, instance, solution, rng);
         let distance = instance.eval(&solution);
+        progress.evaluation(distance);
 
         let f = distance == candidate_a.distance || distance == candidate_b.distance;
 
@@ -233,10 +201,11 @@ impl EvolutionarySolver {
         }
     }
 
-    fn new_candidate(&self, start_vertex: usize, instance: &TSPInstance) -> Candidate {
-        let solution = self.construction_solver.solve(4 + start_vertex, instance);
-        let solution = self.local_solver.solve_s(start_vertex, instance, solution);
+    fn new_candidate(&self, start_vertex: usize, instance: &TSPInstance, progress: &mut Progress, rng: &mut StdRng) -> Candidate {
+        let solution = self.construction_solver.solve(4 + start_vertex, instance, rng);
+        let solution = self.local_solver.solve_s(start_vertex, instance, solution, rng);
         let distance = instance.eval(&solution);
+        progress.evaluation(distance);
         let inbred_count = 0;
         let cross_count = 0;
 
@@ -248,26 +217,27 @@ impl EvolutionarySolver {
         }
     }
 
-    fn perturb(&self, instance: &TSPInstance, mut solution: TSPSolution, neighborhood: &mut Neighborhood, perturb_size: usize) -> TSPSolution {
-        solution.perm_a = solution.perm_a
-            .choose_multiple(&mut rand::thread_rng(), solution.perm_a.len() - perturb_size).cloned().collect();
-        solution.perm_b = solution.perm_b
-            .choose_multiple(&mut rand::thread_rng(), solution.perm_b.len() - perturb_size).cloned().collect();
+    fn perturb(&self, _instance: &TSPInstance, mut solution: TSPSolution, _neighborhood: &mut Neighborhood, perturb_size: usize, rng: &mut StdRng) -> TSPSolution {
+        for perm in solution.perms.iter_mut() {
+            *perm = perm
+                .choose_multiple(rng, perm.len().saturating_sub(perturb_size)).cloned().collect();
+        }
         solution
     }
 
-    fn mutate(&self, instance: &TSPInstance, population: &mut Population, neighborhood: &mut Neighborhood) {
+    fn mutate(&self, instance: &TSPInstance, population: &mut Population, neighborhood: &mut Neighborhood, progress: &mut Progress, rng: &mut StdRng) {
         for candidate in population.pop_registry.iter_mut() {
             let inbreds = candidate.inbred_count as f32;
             let total = candidate.cross_count as f32;
             if candidate.cross_count != 0 && candidate.inbred_count != 0{
                 let mx =  if inbreds/total + 0.1 < 0.8 {inbreds/total + 0.1} else {0.8};
-                let perturb_size = ((instance.dimension as f32) * thread_rng().gen_range(0.1..mx)) as usize / 2;
-                let solution = self.perturb(instance, candidate.solution.clone(), neighborhood, perturb_size);
-                let solution = self.construction_solver.solve_s(0, instance, solution);
+                let perturb_size = ((instance.dimension as f32) * rng.gen_range(0.1..mx)) as usize / instance.no_cycles;
+                let solution = self.perturb(instance, candidate.solution.clone(), neighborhood, perturb_size, rng);
+                let solution = self.construction_solver.solve_s(0, instance, solution, rng);
                 candidate.solution = solution;
                 candidate.solution.reorder();
                 candidate.distance = instance.eval(&candidate.solution);
+                progress.evaluation(candidate.distance);
                 candidate.inbred_count = 0;
                 candidate.cross_count = 0;
             }
@@ -279,21 +249,19 @@ impl EvolutionarySolver {
 
 
 impl Solver<TSPInstance, TSPSolution> for EvolutionarySolver {
-    fn solve(&self, _: usize, instance: &TSPInstance) -> TSPSolution {
+    fn solve(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng) -> TSPSolution {
+        self.solve_observed(start_vertex, instance, rng, &mut NoObserver)
+    }
+
+    fn solve_observed(&self, _: usize, instance: &TSPInstance, rng: &mut StdRng, observer: &mut dyn Observer) -> TSPSolution {
         println!("=====");
-        let EMPTY = TSPSolution {
-            perm_a: vec![],
-            perm_b: vec![],
-            cycle: vec![],
-            order: vec![],
-        };
-        let start = Instant::now();
+        let mut progress = Progress::observed(observer);
 
         let mut population = Population::new();
         let start_each = instance.dimension / self.population_size;
-        let mut best_solution: (f32, usize) = (f32::INFINITY, 0);
+        let mut best_solution: (Cost, usize) = (Cost::MAX, 0);
         for start_i in 0..self.population_size {
-            let candidate = self.new_candidate(start_i * start_each, instance);
+            let candidate = self.new_candidate(start_i * start_each, instance, &mut progress, rng);
             if candidate.distance < best_solution.0 {
                 best_solution = (instance.eval(&candidate.solution), start_i);
                 println!(">> {}", best_solution.0);
@@ -301,10 +269,11 @@ impl Solver<TSPInstance, TSPSolution> for EvolutionarySolver {
             population.register(candidate);
         }
         let mut best_solution = (best_solution.0, population.get(best_solution.1).solution.clone());
-        let mut neighborhood = Neighborhood::new((self.transition)(), &best_solution.1, true);
-        while start.elapsed().as_secs_f32() * 1000.0 < self.time {
+        let mut neighborhood = Neighborhood::new(TransitionKind::create_all(&self.transition), &best_solution.1, true, rng);
+        while !self.termination.reached(&progress) {
             for i in 0..self.steps_to_mutation {
-                match self.cross_over(random_combination(self.population_size), instance, &mut population) {
+                progress.iteration();
+                match self.cross_over(random_combination(self.population_size, rng), instance, &mut population, &mut progress, rng) {
                     Some(candidate) => {
                         if population.has(&candidate) {
                             population.inbred(&candidate);
@@ -319,19 +288,23 @@ impl Solver<TSPInstance, TSPSolution> for EvolutionarySolver {
                     }
                     None => {}
                 }
-                if start.elapsed().as_secs_f32() >= self.time {
+                if self.termination.reached(&progress) {
                     return best_solution.1;
                 }
 
             }
             println!("Mutating");
-            self.mutate(instance, &mut population, &mut neighborhood);
+            self.mutate(instance, &mut population, &mut neighborhood, &mut progress, rng);
         }
 
         best_solution.1
     }
 
-    fn solve_s(&self, start_vertex: usize, instance: &TSPInstance, solution: TSPSolution) -> TSPSolution {
+    fn solve_s(&self, _start_vertex: usize, _instance: &TSPInstance, _solution: TSPSolution, _rng: &mut StdRng) -> TSPSolution {
         unimplemented!()
     }
+
+    fn supports(&self, instance: &TSPInstance) -> bool {
+        self.local_solver.supports(instance) && self.construction_solver.supports(instance)
+    }
 }
@@ -1,46 +1,46 @@
-use std::time::Instant;
-
-use rand::{Rng, thread_rng};
+use rand::Rng;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 
-use crate::traits::{Instance, Solver};
+use crate::traits::{Instance, Solver, Cost, Observer, NoObserver};
 use crate::tsp::def::{TSPInstance, TSPSolution};
 use crate::tsp::neighborhoods::neighborhood::Neighborhood;
-use crate::tsp::neighborhoods::transition::Transition;
+use crate::tsp::neighborhoods::transition_kind::TransitionKind;
 use crate::tsp::solver::GreedySolver;
 use crate::tsp::pickers::regret_picker::RegretPicker;
+use crate::tsp::termination::{Termination, Progress};
 
 pub struct IteratedSolver {
     initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
     sub_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
-    time: f32,
+    termination: Termination,
     perturb_min: f32,
     perturb_max: f32,
-    transition: fn() -> Vec<Box<dyn Transition>>,
+    transition: Vec<TransitionKind>,
 }
 
 
 impl IteratedSolver {
     pub fn new(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
                sub_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
-               time: f32,
+               termination: Termination,
                perturb_min: f32,
                perturb_max: f32,
-               transition: fn() -> Vec<Box<dyn Transition>>) -> IteratedSolver {
+               transition: Vec<TransitionKind>) -> IteratedSolver {
         IteratedSolver {
             initial_solver,
             sub_solver,
-            time,
+            termination,
             perturb_min,
             perturb_max,
             transition,
         }
     }
 
-    fn perturb(&self, instance: &TSPInstance, mut solution: TSPSolution, neighborhood: &mut Neighborhood) -> TSPSolution {
+    fn perturb(&self, instance: &TSPInstance, mut solution: TSPSolution, neighborhood: &mut Neighborhood, rng: &mut StdRng) -> TSPSolution {
         let mut i: f32 = 0.0;
-        let perturb_size = (instance.dimension as f32) * thread_rng().gen_range(self.perturb_min..self.perturb_max);
-        neighborhood.reset();
+        let perturb_size = (instance.dimension as f32) * rng.gen_range(self.perturb_min..self.perturb_max);
+        neighborhood.reset(&solution, rng);
         while i < perturb_size {
             let transition = neighborhood.next(&instance, &solution).unwrap().1;
             neighborhood.apply_transition(transition, &mut solution);
@@ -51,16 +51,23 @@ impl IteratedSolver {
 }
 
 impl Solver<TSPInstance, TSPSolution> for IteratedSolver {
-    fn solve(&self, start_vertex: usize, instance: &TSPInstance) -> TSPSolution {
-        let start = Instant::now();
-        let mut solution = self.initial_solver.solve(start_vertex, instance);
-        let mut best_solution: (f32, TSPSolution) = (instance.eval(&solution), solution);
-        let mut neighborhood = Neighborhood::new((self.transition)(), &best_solution.1, true);
-        while start.elapsed().as_secs_f32() * 1000.0 < self.time {
-            let mut perturb_solution = self.perturb(&instance, best_solution.1.deep_clone(), &mut neighborhood);
+    fn solve(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng) -> TSPSolution {
+        self.solve_observed(start_vertex, instance, rng, &mut NoObserver)
+    }
+
+    fn solve_observed(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng, observer: &mut dyn Observer) -> TSPSolution {
+        let mut progress = Progress::observed(observer);
+        let solution = self.initial_solver.solve(start_vertex, instance, rng);
+        let mut best_solution: (Cost, TSPSolution) = (instance.eval(&solution), solution);
+        progress.evaluation(best_solution.0);
+        let mut neighborhood = Neighborhood::new(TransitionKind::create_all(&self.transition), &best_solution.1, true, rng);
+        while !self.termination.reached(&progress) {
+            let mut perturb_solution = self.perturb(instance, best_solution.1.deep_clone(), &mut neighborhood, rng);
             perturb_solution.reorder();
-            let mut it_solution = self.sub_solver.solve_s(start_vertex, instance, perturb_solution);
+            let it_solution = self.sub_solver.solve_s(start_vertex, instance, perturb_solution, rng);
             let mut it_score = instance.eval(&it_solution);
+            progress.iteration();
+            progress.evaluation(it_score);
             if it_score < best_solution.0 {
                 best_solution = (it_score, it_solution);
             }
@@ -68,62 +75,73 @@ impl Solver<TSPInstance, TSPSolution> for IteratedSolver {
         best_solution.1
     }
 
-    fn solve_s(&self, start_vertex: usize, instance: &TSPInstance, solution: TSPSolution) -> TSPSolution {
+    fn solve_s(&self, _start_vertex: usize, _instance: &TSPInstance, _solution: TSPSolution, _rng: &mut StdRng) -> TSPSolution {
         unimplemented!()
     }
+
+    fn supports(&self, instance: &TSPInstance) -> bool {
+        self.initial_solver.supports(instance) && self.sub_solver.supports(instance)
+    }
 }
 
 pub struct IteratedConstructionSolver {
     initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
     initial_sub_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
     sub_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
-    time: f32,
+    termination: Termination,
     perturb_min: f32,
     perturb_max: f32,
-    transition: fn() -> Vec<Box<dyn Transition>>,
+    transition: Vec<TransitionKind>,
 }
 
 
 impl IteratedConstructionSolver {
     pub fn new(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
                sub_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
-               time: f32,
+               termination: Termination,
                perturb_min: f32,
                perturb_max: f32,
-               transition: fn() -> Vec<Box<dyn Transition>>) -> IteratedConstructionSolver {
+               transition: Vec<TransitionKind>) -> IteratedConstructionSolver {
         let initial_sub_solver = Box::new(GreedySolver::new(Box::new(RegretPicker)));
         IteratedConstructionSolver {
             initial_solver,
             initial_sub_solver,
             sub_solver,
-            time,
+            termination,
             perturb_min,
             perturb_max,
             transition,
         }
     }
 
-    fn perturb(&self, instance: &TSPInstance, mut solution: TSPSolution, neighborhood: &mut Neighborhood) -> TSPSolution {
-        let perturb_size = ((instance.dimension as f32) * thread_rng().gen_range(self.perturb_min..self.perturb_max)) as usize / 2;
-        solution.perm_a = solution.perm_a
-            .choose_multiple(&mut rand::thread_rng(), solution.perm_a.len() - perturb_size).cloned().collect();
-        solution.perm_b = solution.perm_b
-            .choose_multiple(&mut rand::thread_rng(), solution.perm_b.len() - perturb_size).cloned().collect();
+    fn perturb(&self, instance: &TSPInstance, mut solution: TSPSolution, _neighborhood: &mut Neighborhood, rng: &mut StdRng) -> TSPSolution {
+        let perturb_size = ((instance.dimension as f32) * rng.gen_range(self.perturb_min..self.perturb_max)) as usize / instance.no_cycles;
+        for perm in solution.perms.iter_mut() {
+            *perm = perm
+                .choose_multiple(rng, perm.len().saturating_sub(perturb_size)).cloned().collect();
+        }
         solution
     }
 }
 
 impl Solver<TSPInstance, TSPSolution> for IteratedConstructionSolver {
-    fn solve(&self, start_vertex: usize, instance: &TSPInstance) -> TSPSolution {
-        let start = Instant::now();
-        let mut solution = self.initial_solver.solve(start_vertex, instance);
-        let mut best_solution: (f32, TSPSolution) = (instance.eval(&solution), solution);
-        let mut neighborhood = Neighborhood::new((self.transition)(), &best_solution.1, true);
-        while start.elapsed().as_secs_f32() * 1000.0 < self.time {
-            let mut perturb_solution = self.perturb(&instance, best_solution.1.deep_clone(), &mut neighborhood);
-            let mut perturb_solution = self.initial_sub_solver.solve_s(start_vertex, instance, perturb_solution);
-            let mut it_solution = self.sub_solver.solve_s(start_vertex, instance, perturb_solution);
+    fn solve(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng) -> TSPSolution {
+        self.solve_observed(start_vertex, instance, rng, &mut NoObserver)
+    }
+
+    fn solve_observed(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng, observer: &mut dyn Observer) -> TSPSolution {
+        let mut progress = Progress::observed(observer);
+        let solution = self.initial_solver.solve(start_vertex, instance, rng);
+        let mut best_solution: (Cost, TSPSolution) = (instance.eval(&solution), solution);
+        progress.evaluation(best_solution.0);
+        let mut neighborhood = Neighborhood::new(TransitionKind::create_all(&self.transition), &best_solution.1, true, rng);
+        while !self.termination.reached(&progress) {
+            let perturb_solution = self.perturb(instance, best_solution.1.deep_clone(), &mut neighborhood, rng);
+            let perturb_solution = self.initial_sub_solver.solve_s(start_vertex, instance, perturb_solution, rng);
+            let it_solution = self.sub_solver.solve_s(start_vertex, instance, perturb_solution, rng);
             let mut it_score = instance.eval(&it_solution);
+            progress.iteration();
+            progress.evaluation(it_score);
             if it_score < best_solution.0 {
                 best_solution = (it_score, it_solution);
             }
@@ -131,7 +149,11 @@ impl Solver<TSPInstance, TSPSolution> for IteratedConstructionSolver {
         best_solution.1
     }
 
-    fn solve_s(&self, start_vertex: usize, instance: &TSPInstance, solution: TSPSolution) -> TSPSolution {
+    fn solve_s(&self, _start_vertex: usize, _instance: &TSPInstance, _solution: TSPSolution, _rng: &mut StdRng) -> TSPSolution {
         unimplemented!()
     }
+
+    fn supports(&self, instance: &TSPInstance) -> bool {
+        self.initial_solver.supports(instance) && self.initial_sub_solver.supports(instance) && self.sub_solver.supports(instance)
+    }
 }
\ No newline at end of file
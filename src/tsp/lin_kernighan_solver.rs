@@ -0,0 +1,256 @@
+use std::collections::VecDeque;
+
+use crate::tsp::neighborhoods::inter_cycle_transition::InterCycleTransition;
+use crate::tsp::def::{TSPSolution, TSPInstance, SHARED};
+use rand::rngs::StdRng;
+use crate::traits::{Solver, Cost};
+
+// Variable-depth search in the spirit of Lin-Kernighan. Inside a cycle the edge (t1, t2) is removed and
+// a chain of 2-opt moves keeps adding an edge from t2 to one of its nearest neighbors t3 and removing the edge
+// in front of t3, for as long as the gain without the closing edge stays positive. The best prefix of the chain is kept.
+// Across cycles vertices are swapped in Kernighan-Lin fashion, allowing worse swaps on the way to a better sequence.
+pub struct LinKernighanSolver {
+    initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
+    num_neighbors: usize,
+    max_depth: usize,
+    inter_cycle: bool,
+}
+
+impl LinKernighanSolver {
+    pub fn new(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>, num_neighbors: usize, max_depth: usize, inter_cycle: bool) -> LinKernighanSolver {
+        LinKernighanSolver {
+            initial_solver,
+            num_neighbors,
+            max_depth,
+            inter_cycle,
+        }
+    }
+
+    // Neighbor of the vertex in the direction of the tour, forward follows the order of its perm
+    fn succ(&self, vertex: usize, forward: bool, solution: &TSPSolution) -> usize {
+        let perm = &solution.perms[solution.cycle[vertex]];
+        let n = perm.len();
+        if forward {
+            perm[(solution.order[vertex]+1)%n]
+        } else {
+            perm[(solution.order[vertex]+n-1)%n]
+        }
+    }
+
+    // Reverses perm[from..=to] going around the end of the perm if needed
+    fn reverse(&self, cycle: usize, from: usize, to: usize, solution: &mut TSPSolution) {
+        let perm = &mut solution.perms[cycle];
+        let n = perm.len();
+        let length = (to+n-from)%n + 1;
+        for k in 0..length/2 {
+            let (a, b) = ((from+k)%n, (to+n-k)%n);
+            perm.swap(a, b);
+            solution.order[perm[a]] = a;
+            solution.order[perm[b]] = b;
+        }
+    }
+
+    // Reverses the path between positions from and to, or the rest of the cycle which gives the same cycle,
+    // returns the reversed range so that it can be undone. A shared depot in front of the perm is never moved.
+    fn flip(&self, cycle: usize, from: usize, to: usize, solution: &mut TSPSolution) -> (usize, usize, usize) {
+        let n = solution.perms[cycle].len();
+        let length = (to+n-from)%n + 1;
+        let (rest_from, rest_to) = ((to+1)%n, (from+n-1)%n);
+        let depot = solution.cycle[solution.perms[cycle][0]] == SHARED;
+        let contains_front = from > to || from == 0;
+        let range = if (depot && contains_front) || (!depot && 2*length > n) {
+            (rest_from, rest_to)
+        } else {
+            (from, to)
+        };
+        self.reverse(cycle, range.0, range.1, solution);
+        (cycle, range.0, range.1)
+    }
+
+    // One chain of sequential exchanges starting with the removal of (t1, t2) and the addition of (t2, t3),
+    // returns the gain of the kept prefix and the vertices at the ends of the changed edges
+    fn chain(&self, t1: usize, forward: bool, t3: usize, neighbors: &[Vec<usize>], instance: &TSPInstance, solution: &mut TSPSolution) -> Option<(Cost, Vec<usize>)> {
+        let cycle = solution.cycle[t1];
+        let (mut forward, mut t2, mut t3) = (forward, self.succ(t1, forward, solution), Some(t3));
+        let mut gain = instance.dist_k(t1, t2);
+        let mut flips = vec![];
+        let mut touched = vec![t1, t2];
+        let mut added: Vec<(usize, usize)> = vec![];
+        let mut best: (Cost, usize, usize) = (0, 0, 0);
+        while let Some(next) = t3 {
+            let t4 = self.succ(next, !forward, solution);
+            gain += instance.dist_k(t4, next) - instance.dist_k(t2, next);
+            let (from, to) = if forward {
+                (solution.order[t2], solution.order[t4])
+            } else {
+                (solution.order[t4], solution.order[t2])
+            };
+            flips.push(self.flip(cycle, from, to, solution));
+            added.push((t2, next));
+            touched.extend([next, t4]);
+            forward = self.succ(t1, true, solution) == t4;
+            t2 = t4;
+            if gain - instance.dist_k(t1, t2) > best.0 {
+                best = (gain - instance.dist_k(t1, t2), flips.len(), touched.len());
+            }
+            t3 = if flips.len() < self.max_depth {
+                self.steps((t1, t2, forward), gain, &added, neighbors, instance, solution).first().map(|(_, t3)| *t3)
+            } else {
+                None
+            };
+        }
+        for (cycle, from, to) in flips.drain(best.1..).rev() {
+            self.reverse(cycle, from, to, solution);
+        }
+        touched.truncate(best.2);
+        if best.0 > 0 {
+            Some((best.0, touched))
+        } else {
+            None
+        }
+    }
+
+    // Candidates t3 for adding (t2, t3) with the gain criterion satisfied, the best one first
+    fn steps(&self, (t1, t2, forward): (usize, usize, bool), gain: Cost, added: &[(usize, usize)], neighbors: &[Vec<usize>], instance: &TSPInstance, solution: &TSPSolution) -> Vec<(Cost, usize)> {
+        let mut steps = vec![];
+        for t3 in neighbors[t2].iter().cloned() {
+            if solution.cycle[t3] != solution.cycle[t1] || t3 == t1 || t3 == self.succ(t2, forward, solution) {
+                continue;
+            }
+            let t4 = self.succ(t3, !forward, solution);
+            if !solution.is_exclusive(t4) || t4 == t2 || added.contains(&(t4, t3)) || added.contains(&(t3, t4)) {
+                continue;
+            }
+            if gain - instance.dist_k(t2, t3) <= 0 {
+                continue;
+            }
+            steps.push((instance.dist_k(t4, t3) - instance.dist_k(t2, t3), t3));
+        }
+        steps.sort_by_key(|(step, _)| -step);
+        steps
+    }
+
+    // Tries both edges at t1 and every first step, deeper steps follow the best candidate only
+    fn improve(&self, t1: usize, neighbors: &[Vec<usize>], instance: &TSPInstance, solution: &mut TSPSolution) -> Option<Vec<usize>> {
+        for forward in [true, false] {
+            let t2 = self.succ(t1, forward, solution);
+            if !solution.is_exclusive(t2) {
+                continue;
+            }
+            let gain = instance.dist_k(t1, t2);
+            for (_, t3) in self.steps((t1, t2, forward), gain, &[], neighbors, instance, solution) {
+                if let Some((_, touched)) = self.chain(t1, forward, t3, neighbors, instance, solution) {
+                    return Some(touched);
+                }
+            }
+        }
+        None
+    }
+
+    // Sequence of the best vertex swaps between cycles, each vertex swapped at most once, the best prefix is kept.
+    // A vertex is swapped with one of its neighbors in another cycle or with the vertices on both sides of it.
+    fn exchange(&self, neighbors: &[Vec<usize>], instance: &TSPInstance, solution: &mut TSPSolution) -> Option<Vec<usize>> {
+        let transition = InterCycleTransition::new();
+        let mut locked = vec![false; instance.dimension];
+        let mut swaps = vec![];
+        let mut touched = vec![];
+        let mut gain = 0;
+        let mut best: (Cost, usize) = (0, 0);
+        for _ in 0..self.max_depth {
+            let mut best_swap: Option<(Cost, (usize, usize, usize, usize))> = None;
+            for a in (0..instance.dimension).filter(|a| solution.is_exclusive(*a) && !locked[*a]) {
+                let (cycle_a, id_a) = (solution.cycle[a], solution.order[a]);
+                for u in neighbors[a].iter().filter(|u| solution.is_exclusive(**u) && solution.cycle[**u] != cycle_a) {
+                    let cycle_b = solution.cycle[*u];
+                    let n_b = solution.perms[cycle_b].len();
+                    for id_b in [(solution.order[*u]+n_b-1)%n_b, solution.order[*u], (solution.order[*u]+1)%n_b] {
+                        if locked[solution.perms[cycle_b][id_b]] {
+                            continue;
+                        }
+                        if let Some(score) = transition.score_explicit(cycle_a, id_a, cycle_b, id_b, instance, solution) {
+                            if best_swap.is_none_or(|(best_score, _)| score < best_score) {
+                                best_swap = Some((score, (cycle_a, id_a, cycle_b, id_b)));
+                            }
+                        }
+                    }
+                }
+            }
+            let (score, (cycle_a, id_a, cycle_b, id_b)) = match best_swap {
+                Some(swap) => swap,
+                None => break,
+            };
+            let (a, b) = (solution.perms[cycle_a][id_a], solution.perms[cycle_b][id_b]);
+            transition.apply_explicit(cycle_a, id_a, cycle_b, id_b, solution);
+            locked[a] = true;
+            locked[b] = true;
+            for vertex in [a, b] {
+                touched.extend([self.succ(vertex, false, solution), vertex, self.succ(vertex, true, solution)]);
+            }
+            swaps.push((cycle_a, id_a, cycle_b, id_b));
+            gain -= score;
+            if gain > best.0 {
+                best = (gain, swaps.len());
+            }
+        }
+        // a swap is undone by swapping the same positions again
+        for (cycle_a, id_a, cycle_b, id_b) in swaps.drain(best.1..).rev() {
+            transition.apply_explicit(cycle_a, id_a, cycle_b, id_b, solution);
+        }
+        touched.truncate(6*best.1);
+        if best.0 > 0 {
+            Some(touched)
+        } else {
+            None
+        }
+    }
+}
+
+impl Solver<TSPInstance, TSPSolution> for LinKernighanSolver {
+    fn solve(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng) -> TSPSolution {
+        let solution = self.initial_solver.solve(start_vertex, instance, rng);
+        self.solve_s(start_vertex, instance, solution, rng)
+    }
+
+    fn solve_s(&self, _start_vertex: usize, instance: &TSPInstance, mut solution: TSPSolution, _rng: &mut StdRng) -> TSPSolution {
+        // reversed segments change the tour length of asymmetric instances, callers are expected to check supports()
+        if !instance.symmetric {
+            return solution;
+        }
+        let neighbors = instance.nearest_neighbors(self.num_neighbors);
+        let mut queued = vec![false; instance.dimension];
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        let wake = |vertices: &[usize], queue: &mut VecDeque<usize>, queued: &mut Vec<bool>| {
+            for vertex in vertices.iter().cloned() {
+                if !queued[vertex] {
+                    queued[vertex] = true;
+                    queue.push_back(vertex);
+                }
+            }
+        };
+        let vertices: Vec<usize> = (0..instance.dimension).filter(|vertex| solution.is_exclusive(*vertex)).collect();
+        wake(&vertices, &mut queue, &mut queued);
+        loop {
+            while let Some(t1) = queue.pop_front() {
+                queued[t1] = false;
+                if !solution.is_exclusive(t1) {
+                    continue;
+                }
+                if let Some(touched) = self.improve(t1, &neighbors, instance, &mut solution) {
+                    wake(&touched, &mut queue, &mut queued);
+                }
+            }
+            if !self.inter_cycle {
+                break;
+            }
+            match self.exchange(&neighbors, instance, &mut solution) {
+                Some(touched) => wake(&touched, &mut queue, &mut queued),
+                None => break,
+            }
+        }
+        solution
+    }
+
+    fn supports(&self, instance: &TSPInstance) -> bool {
+        instance.symmetric && self.initial_solver.supports(instance)
+    }
+}
@@ -1,38 +1,54 @@
 use std::time::Instant;
 
-use crate::tsp::neighborhoods::transition::Transition;
+use crate::tsp::neighborhoods::transition_kind::TransitionKind;
 use crate::tsp::neighborhoods::neighborhood::Neighborhood;
+use crate::tsp::neighborhoods::dont_look_bits::DontLookBits;
 use crate::tsp::def::TSPSolution;
 use crate::tsp::def::TSPInstance;
-use crate::traits::{Solver, Instance};
+use rand::rngs::StdRng;
+use crate::traits::{Solver, Instance, Cost};
 
 pub struct LocalGreedySolver {
     initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
-    transition: fn() -> Vec<Box<dyn Transition>>
+    transition: Vec<TransitionKind>,
+    dont_look_bits: Option<DontLookBits>,
 }
 
 impl LocalGreedySolver {
-    pub fn new(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>, transition: fn() -> Vec<Box<dyn Transition>>) -> LocalGreedySolver {
+    pub fn new(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>, transition: Vec<TransitionKind>) -> LocalGreedySolver {
         LocalGreedySolver {
             initial_solver,
-            transition
+            transition,
+            dont_look_bits: None,
+        }
+    }
+
+    // Looks only at moves towards the num_neighbors nearest vertices, around vertices whose edges changed
+    pub fn with_dont_look_bits(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>, transition: Vec<TransitionKind>, num_neighbors: usize) -> LocalGreedySolver {
+        LocalGreedySolver {
+            initial_solver,
+            transition,
+            dont_look_bits: Some(DontLookBits::new(num_neighbors, false)),
         }
     }
 }
 
 impl Solver<TSPInstance, TSPSolution> for LocalGreedySolver {
-    fn solve(&self, start_vertex: usize, instance: &TSPInstance) -> TSPSolution {
-        let mut solution = self.initial_solver.solve(start_vertex, instance);
-        let mut neighborhood = Neighborhood::new((self.transition)(), &solution, true);
+    fn solve(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng) -> TSPSolution {
+        let mut solution = self.initial_solver.solve(start_vertex, instance, rng);
+        if let Some(dont_look_bits) = &self.dont_look_bits {
+            return dont_look_bits.search(TransitionKind::create_all(&self.transition), instance, solution, rng);
+        }
+        let mut neighborhood = Neighborhood::new(TransitionKind::create_all(&self.transition), &solution, true, rng);
         let mut improvement_flag = true;
         while improvement_flag {
             improvement_flag = false;
-            neighborhood.reset();
+            neighborhood.reset(&solution, rng);
             loop {
                 let score = neighborhood.next(instance, &solution);
                 match score {
                     Some(x) => {
-                        if x.0 < 0.0 {
+                        if x.0 < 0 {
                             improvement_flag = true;
                             // let a = instance.eval(&solution);
                             neighborhood.apply_transition(x.1, &mut solution);
@@ -51,34 +67,52 @@ impl Solver<TSPInstance, TSPSolution> for LocalGreedySolver {
         solution
     }
 
-    fn solve_s(&self, start_vertex: usize, instance: &TSPInstance, solution: TSPSolution) -> TSPSolution {
+    fn solve_s(&self, _start_vertex: usize, _instance: &TSPInstance, _solution: TSPSolution, _rng: &mut StdRng) -> TSPSolution {
         unimplemented!()
     }
+
+    fn supports(&self, instance: &TSPInstance) -> bool {
+        self.initial_solver.supports(instance)
+    }
 }
 
 pub struct LocalSteepestSolver {
     initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
-    transition: fn() -> Vec<Box<dyn Transition>>
+    transition: Vec<TransitionKind>,
+    dont_look_bits: Option<DontLookBits>,
 }
 
 impl LocalSteepestSolver {
-    pub fn new(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>, transition: fn() -> Vec<Box<dyn Transition>>) -> LocalSteepestSolver {
+    pub fn new(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>, transition: Vec<TransitionKind>) -> LocalSteepestSolver {
         LocalSteepestSolver {
             initial_solver,
-            transition
+            transition,
+            dont_look_bits: None,
+        }
+    }
+
+    // Looks only at moves towards the num_neighbors nearest vertices, around vertices whose edges changed
+    pub fn with_dont_look_bits(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>, transition: Vec<TransitionKind>, num_neighbors: usize) -> LocalSteepestSolver {
+        LocalSteepestSolver {
+            initial_solver,
+            transition,
+            dont_look_bits: Some(DontLookBits::new(num_neighbors, true)),
         }
     }
 }
 
 impl Solver<TSPInstance, TSPSolution> for LocalSteepestSolver {
-    fn solve(&self, start_vertex: usize, instance: &TSPInstance) -> TSPSolution {
-        let mut solution = self.initial_solver.solve(start_vertex, instance);
-        let mut neighborhood = Neighborhood::new((self.transition)(), &solution, false);
+    fn solve(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng) -> TSPSolution {
+        let mut solution = self.initial_solver.solve(start_vertex, instance, rng);
+        if let Some(dont_look_bits) = &self.dont_look_bits {
+            return dont_look_bits.search(TransitionKind::create_all(&self.transition), instance, solution, rng);
+        }
+        let mut neighborhood = Neighborhood::new(TransitionKind::create_all(&self.transition), &solution, false, rng);
         let mut improvement_flag = true;
         while improvement_flag {
             improvement_flag = false;
-            neighborhood.reset();
-            let mut best_move: (f32, usize) = (1.0, 0);
+            neighborhood.reset(&solution, rng);
+            let mut best_move: (Cost, usize) = (0, 0);
             loop {
                 let score = neighborhood.next(instance, &solution);
                 match score {
@@ -90,7 +124,7 @@ impl Solver<TSPInstance, TSPSolution> for LocalSteepestSolver {
                     None => break
                 }
             }
-            if best_move.0 < 0.0 {
+            if best_move.0 < 0 {
                 neighborhood.apply_transition(best_move.1, &mut solution);
                 improvement_flag = true;
             }
@@ -98,18 +132,22 @@ impl Solver<TSPInstance, TSPSolution> for LocalSteepestSolver {
         solution
     }
 
-    fn solve_s(&self, start_vertex: usize, instance: &TSPInstance, solution: TSPSolution) -> TSPSolution {
+    fn solve_s(&self, _start_vertex: usize, _instance: &TSPInstance, _solution: TSPSolution, _rng: &mut StdRng) -> TSPSolution {
         unimplemented!()
     }
+
+    fn supports(&self, instance: &TSPInstance) -> bool {
+        self.initial_solver.supports(instance)
+    }
 }
 
 pub struct LocalRandomWalker {
     initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
-    transition: fn() -> Vec<Box<dyn Transition>>
+    transition: Vec<TransitionKind>
 }
 
 impl LocalRandomWalker {
-    pub fn new(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>, transition: fn() -> Vec<Box<dyn Transition>>) -> LocalRandomWalker {
+    pub fn new(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>, transition: Vec<TransitionKind>) -> LocalRandomWalker {
         LocalRandomWalker {
             initial_solver,
             transition
@@ -118,14 +156,14 @@ impl LocalRandomWalker {
 }
 
 impl Solver<TSPInstance, TSPSolution> for LocalRandomWalker {
-    fn solve(&self, start_vertex: usize, instance: &TSPInstance) -> TSPSolution {
+    fn solve(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng) -> TSPSolution {
         let start = Instant::now();
-        let mut solution = self.initial_solver.solve(start_vertex, instance);
-        let mut neighborhood = Neighborhood::new((self.transition)(), &solution, true);
+        let mut solution = self.initial_solver.solve(start_vertex, instance, rng);
+        let mut neighborhood = Neighborhood::new(TransitionKind::create_all(&self.transition), &solution, true, rng);
         let mut current_score = instance.eval(&solution);
         let mut best_solution = (current_score, solution.clone());
         while  start.elapsed().as_secs_f32() < 1.0 {
-            neighborhood.reset();
+            neighborhood.reset(&solution, rng);
             loop {
                 let score = neighborhood.next(instance, &solution);
                 match score {
@@ -150,7 +188,11 @@ impl Solver<TSPInstance, TSPSolution> for LocalRandomWalker {
         best_solution.1
     }
 
-    fn solve_s(&self, start_vertex: usize, instance: &TSPInstance, solution: TSPSolution) -> TSPSolution {
+    fn solve_s(&self, _start_vertex: usize, _instance: &TSPInstance, _solution: TSPSolution, _rng: &mut StdRng) -> TSPSolution {
         unimplemented!()
     }
+
+    fn supports(&self, instance: &TSPInstance) -> bool {
+        self.initial_solver.supports(instance)
+    }
 }
\ No newline at end of file
@@ -1,15 +1,13 @@
-use crate::tsp::neighborhoods::neighborhood::Neighborhood;
-use std::time::Instant;
-use std::cmp;
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 
-use crate::tsp::neighborhoods::transition::Transition;
 use crate::tsp::neighborhoods::inter_cycle_transition::InterCycleTransition;
 use crate::tsp::neighborhoods::edges_transition::EdgesTransition;
+use crate::tsp::neighborhoods::cross_exchange_transition::{CrossExchangeTransition, Exchange};
 use crate::tsp::def::TSPSolution;
 use crate::tsp::def::TSPInstance;
-use crate::traits::{Solver, Instance};
+use rand::rngs::StdRng;
+use crate::traits::{Solver, Cost};
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 struct State {
@@ -20,7 +18,17 @@ struct State {
     b: usize,
     b_next: usize,
     inter_cycle: bool,
-    score: isize,
+    // segments starting at a and b exchanged between their cycles
+    segments: Option<Segments>,
+    score: Cost,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+struct Segments {
+    length_a: usize,
+    length_b: usize,
+    reversed_a: bool,
+    reversed_b: bool,
 }
 
 impl Ord for State {
@@ -37,50 +45,83 @@ impl PartialOrd for State {
 
 pub struct MemorySolver {
     initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
+    cross_exchange: Option<CrossExchangeTransition>,
 }
 
 impl MemorySolver {
     pub fn new(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>) -> MemorySolver {
         MemorySolver {
-            initial_solver
+            initial_solver,
+            cross_exchange: None,
+        }
+    }
+
+    // Also keeps exchanges of segments of up to max_length vertices between the cycles in the queue
+    pub fn with_cross_exchange(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>, max_length: usize) -> MemorySolver {
+        MemorySolver {
+            initial_solver,
+            cross_exchange: Some(CrossExchangeTransition::with_max_length(max_length)),
         }
     }
 
     fn get_new_inter_cycle_state(&self, a_prev: usize, a: usize, a_next: usize, b_prev: usize, b: usize, b_next: usize, instance: &TSPInstance) -> State {
-        let mut s = State{a_prev, a, a_next, b_prev, b, b_next, inter_cycle: true, score: 0};
+        let mut s = State{a_prev, a, a_next, b_prev, b, b_next, inter_cycle: true, segments: None, score: 0};
         s.score = self.get_score(&s, instance);
         s
     }
 
     fn get_new_edges_state(&self, a: usize, a_next: usize, b: usize, b_next: usize, instance: &TSPInstance) -> State {
-        let mut s = State{a_prev: 0, a, a_next, b_prev: 0, b, b_next, inter_cycle: false, score: 0};
+        let mut s = State{a_prev: 0, a, a_next, b_prev: 0, b, b_next, inter_cycle: false, segments: None, score: 0};
         s.score = self.get_score(&s, instance);
         s
     }
 
+    fn get_exchange(&self, a: usize, b: usize, segments: &Segments, solution: &TSPSolution) -> Exchange {
+        Exchange {
+            cycle_a: solution.cycle[a],
+            start_a: solution.order[a],
+            length_a: segments.length_a,
+            cycle_b: solution.cycle[b],
+            start_b: solution.order[b],
+            length_b: segments.length_b,
+            reversed_a: segments.reversed_a,
+            reversed_b: segments.reversed_b,
+        }
+    }
+
     fn get_neighbors_in_cycle(&self, id: usize, perm:  &Vec<usize>) -> (usize, usize, usize) {
         let n = perm.len();
         (perm[(id+n-1)%n], perm[id], perm[(id+1)%n])
     }
 
-    fn get_score(&self, s: &State, instance: &TSPInstance) -> isize {
+    fn get_score(&self, s: &State, instance: &TSPInstance) -> Cost {
         if s.inter_cycle {
-            (instance.dist_k(s.b_prev, s.a) + instance.dist_k(s.a, s.b_next)
+            instance.dist_k(s.b_prev, s.a) + instance.dist_k(s.a, s.b_next)
             + instance.dist_k(s.a_prev, s.b) + instance.dist_k(s.b, s.a_next)
             - instance.dist_k(s.a_prev, s.a) - instance.dist_k(s.a, s.a_next)
-            - instance.dist_k(s.b_prev, s.b) - instance.dist_k(s.b, s.b_next)) as isize
+            - instance.dist_k(s.b_prev, s.b) - instance.dist_k(s.b, s.b_next)
         } else {
-            (instance.dist_k(s.a, s.b) + instance.dist_k(s.a_next, s.b_next)
-            - instance.dist_k(s.a, s.a_next) - instance.dist_k(s.b, s.b_next)) as isize
+            instance.dist_k(s.a, s.b) + instance.dist_k(s.a_next, s.b_next)
+            - instance.dist_k(s.a, s.a_next) - instance.dist_k(s.b, s.b_next)
         }
     }
 
     fn is_valid(&self, s: &State, instance: &TSPInstance, solution: &TSPSolution) -> (Option<State>, usize) {
-        let (a_prev, a, a_next) = self.get_neighbors_in_cycle(solution.order[s.a], if solution.cycle[s.a] == 0 { &solution.perm_a } else { &solution.perm_b });
-        let (b_prev, b, b_next) = self.get_neighbors_in_cycle(solution.order[s.b], if solution.cycle[s.b] == 0 { &solution.perm_a } else { &solution.perm_b });
+        if let (Some(segments), Some(cross_exchange)) = (&s.segments, &self.cross_exchange) {
+            if !solution.is_exclusive(s.a) || !solution.is_exclusive(s.b) {
+                return (None, 0);
+            }
+            let exchange = self.get_exchange(s.a, s.b, segments, solution);
+            return match cross_exchange.score_explicit(&exchange, instance, solution) {
+                Some(score) if score == s.score => (Some(*s), 2),
+                _ => (None, 0),
+            };
+        }
+        let (a_prev, a, a_next) = self.get_neighbors_in_cycle(solution.order[s.a], &solution.perms[solution.cycle[s.a]]);
+        let (b_prev, b, b_next) = self.get_neighbors_in_cycle(solution.order[s.b], &solution.perms[solution.cycle[s.b]]);
         if s.inter_cycle {
             let ns = self.get_new_inter_cycle_state(a_prev, a, a_next, b_prev, b, b_next, instance);
-            if solution.cycle[s.a] == solution.cycle[s.b] {
+            if solution.cycle[s.a] == solution.cycle[s.b] || !instance.allows_swap(solution, s.a, s.b) {
                 (None, 0)
             } else if  ns.score == s.score {
                 (Some(ns), 2)
@@ -103,18 +144,36 @@ impl MemorySolver {
     }
 
     fn apply(&self, s: &State, instance: &TSPInstance, solution: &mut TSPSolution, q: &mut BinaryHeap<State>) {
-        let (a_prev, a, a_next) = self.get_neighbors_in_cycle(solution.order[s.a], if solution.cycle[s.a] == 0 { &solution.perm_a } else { &solution.perm_b });
-        let (b_prev, b, b_next) = self.get_neighbors_in_cycle(solution.order[s.b], if solution.cycle[s.b] == 0 { &solution.perm_a } else { &solution.perm_b });    
+        let (a_prev, a, a_next) = self.get_neighbors_in_cycle(solution.order[s.a], &solution.perms[solution.cycle[s.a]]);
+        let (b_prev, b, b_next) = self.get_neighbors_in_cycle(solution.order[s.b], &solution.perms[solution.cycle[s.b]]);    
         let vertices_to_update;
         let edges_to_update;
-        if s.inter_cycle {
+        if let (Some(segments), Some(cross_exchange)) = (&s.segments, &self.cross_exchange) {
+            let exchange = self.get_exchange(s.a, s.b, segments, solution);
+            cross_exchange.apply_explicit(&exchange, solution);
+            // the vertices next to the exchanged ones get new neighbors, segments starting up to max_length
+            // vertices before them overlap them only when they are long enough to reach them
+            let mut vertices = vec![];
+            let mut edges = vec![];
+            for (cycle, start, length) in [(exchange.cycle_a, exchange.start_a, exchange.length_b), (exchange.cycle_b, exchange.start_b, exchange.length_a)] {
+                let perm = &solution.perms[cycle];
+                let n = perm.len();
+                for i in start+n-cross_exchange.max_length().min(n)..start+n-1 {
+                    self.update_segments(perm[i%n], start+n-i, cross_exchange, instance, solution, q);
+                }
+                for i in start+n-1..=start+n+length {
+                    vertices.push(perm[i%n]);
+                }
+                for i in start+n-1..start+n+length {
+                    edges.push((perm[i%n], perm[(i+1)%n]));
+                }
+            }
+            vertices_to_update = vertices;
+            edges_to_update = edges;
+        } else if s.inter_cycle {
             assert_ne!(solution.cycle[s.a], solution.cycle[s.b]);
             let inter_cycle_transition = InterCycleTransition::new();
-            if solution.cycle[s.a] == 0 {
-                inter_cycle_transition.apply_explicit(solution.order[s.a], solution.order[s.b], solution);
-            } else {
-                inter_cycle_transition.apply_explicit(solution.order[s.b], solution.order[s.a], solution);
-            }
+            inter_cycle_transition.apply_explicit(solution.cycle[s.a], solution.order[s.a], solution.cycle[s.b], solution.order[s.b], solution);
             vertices_to_update = vec![a_prev, a, a_next, b_prev, b, b_next];
             edges_to_update = vec![(a_prev, b), (b, a_next), (b_prev, a), (a, b_next)];
         } else  {
@@ -133,16 +192,19 @@ impl MemorySolver {
     }
 
     fn update_edge(&self, edge: (usize, usize), instance: &TSPInstance, solution: &TSPSolution, q: &mut BinaryHeap<State>) {
+        if !solution.is_exclusive(edge.0) {
+            return;
+        }
         for b in 0..instance.dimension {
             if solution.cycle[edge.0] == solution.cycle[b] {
-                let (_, b, b_next) = self.get_neighbors_in_cycle(solution.order[b], if solution.cycle[b] == 0 { &solution.perm_a } else { &solution.perm_b });
+                let (_, b, b_next) = self.get_neighbors_in_cycle(solution.order[b], &solution.perms[solution.cycle[b]]);
                 if b != edge.0 && b != edge.1 && b_next != edge.0 && b_next != edge.1 {
                     let s = self.get_new_edges_state(edge.0, edge.1, b, b_next, instance);
                     if s.score < 0 {
                         q.push(s);
                     }
                     let ns = self.get_new_edges_state(edge.0, edge.1, b_next, b, instance);
-                    if ns.score < 0 {
+                    if ns.score < 0 && solution.is_exclusive(b_next) {
                         q.push(ns);
                     }
                 } 
@@ -151,32 +213,70 @@ impl MemorySolver {
     }
 
     fn update_vertex(&self, a: usize, instance: &TSPInstance, solution: &TSPSolution, q: &mut BinaryHeap<State>) {
-        let (a_prev, a, a_next) = self.get_neighbors_in_cycle(solution.order[a], if solution.cycle[a] == 0 { &solution.perm_a } else { &solution.perm_b });
+        if !solution.is_exclusive(a) {
+            return;
+        }
+        let (a_prev, a, a_next) = self.get_neighbors_in_cycle(solution.order[a], &solution.perms[solution.cycle[a]]);
         for b in 0..instance.dimension {
-            if solution.cycle[a] != solution.cycle[b] {
-                let (b_prev, b, b_next) = self.get_neighbors_in_cycle(solution.order[b], if solution.cycle[b] == 0 { &solution.perm_a } else { &solution.perm_b });
+            if solution.cycle[a] != solution.cycle[b] && solution.is_exclusive(b) {
+                let (b_prev, b, b_next) = self.get_neighbors_in_cycle(solution.order[b], &solution.perms[solution.cycle[b]]);
                 let s = self.get_new_inter_cycle_state(a_prev, a, a_next, b_prev, b, b_next, instance);
                 if s.score < 0 {
                     q.push(s);
                 }
             }
         }
+        if let Some(cross_exchange) = &self.cross_exchange {
+            self.update_segments(a, 1, cross_exchange, instance, solution, q);
+        }
+    }
+
+    // Exchanges of the segments starting at a that are at least min_length long
+    fn update_segments(&self, a: usize, min_length: usize, cross_exchange: &CrossExchangeTransition, instance: &TSPInstance, solution: &TSPSolution, q: &mut BinaryHeap<State>) {
+        if min_length > cross_exchange.max_length() || !solution.is_exclusive(a) {
+            return;
+        }
+        for b in 0..instance.dimension {
+            if solution.cycle[a] == solution.cycle[b] || !solution.is_exclusive(b) {
+                continue;
+            }
+            for length_a in min_length..=cross_exchange.max_length() {
+                for length_b in 1..=cross_exchange.max_length() {
+                    for (reversed_a, reversed_b) in [(false, false), (true, false), (false, true), (true, true)] {
+                        let segments = Segments { length_a, length_b, reversed_a, reversed_b };
+                        let exchange = self.get_exchange(a, b, &segments, solution);
+                        if let Some(score) = cross_exchange.score_explicit(&exchange, instance, solution) {
+                            if score < 0 {
+                                q.push(State { a_prev: 0, a, a_next: 0, b_prev: 0, b, b_next: 0, inter_cycle: true, segments: Some(segments), score });
+                            }
+                        }
+                    }
+                }
+            }
+        }
     }
 
 }
 
 impl Solver<TSPInstance, TSPSolution> for MemorySolver {
-    fn solve(&self, start_vertex: usize, instance: &TSPInstance) -> TSPSolution {
-        let mut solution = self.initial_solver.solve(start_vertex, instance);
-        self.solve_s(start_vertex, instance, solution)
+    fn solve(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng) -> TSPSolution {
+        let solution = self.initial_solver.solve(start_vertex, instance, rng);
+        self.solve_s(start_vertex, instance, solution, rng)
     }
 
-    fn solve_s(&self, start_vertex: usize, instance: &TSPInstance, mut solution: TSPSolution) -> TSPSolution {
+    fn solve_s(&self, _start_vertex: usize, instance: &TSPInstance, mut solution: TSPSolution, _rng: &mut StdRng) -> TSPSolution {
+        // the cached deltas assume symmetric distances, callers are expected to check supports()
+        if !instance.symmetric {
+            return solution;
+        }
         let mut improvement_flag = true;
         let mut q: BinaryHeap<State> = BinaryHeap::new();
         for i in 0..instance.dimension {
             self.update_vertex(i, instance, &solution, &mut q);
-            let (_, _, i_next) = self.get_neighbors_in_cycle(solution.order[i], if solution.cycle[i] == 0 { &solution.perm_a } else { &solution.perm_b });
+            if !solution.is_exclusive(i) {
+                continue;
+            }
+            let (_, _, i_next) = self.get_neighbors_in_cycle(solution.order[i], &solution.perms[solution.cycle[i]]);
             self.update_edge((i, i_next), instance, &solution, &mut q);
         }
         while improvement_flag {
@@ -227,5 +327,9 @@ impl Solver<TSPInstance, TSPSolution> for MemorySolver {
         }
         solution
     }
+
+    fn supports(&self, instance: &TSPInstance) -> bool {
+        instance.symmetric && self.initial_solver.supports(instance)
+    }
 }
 
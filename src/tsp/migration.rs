@@ -0,0 +1,64 @@
+use json;
+use rand::Rng;
+use rand::rngs::StdRng;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Topology {
+    #[default]
+    Ring,
+    Full,
+    Random,
+}
+
+// Exchange of the best candidates between islands, every `interval` generations each island sends its `migrants` best,
+// an interval of 0 turns migration off
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    pub interval: usize,
+    pub migrants: usize,
+    pub topology: Topology,
+}
+
+impl Default for Migration {
+    fn default() -> Self {
+        Migration {
+            interval: 4,
+            migrants: 1,
+            topology: Topology::Ring,
+        }
+    }
+}
+
+impl Migration {
+    pub fn from_json(config: &json::JsonValue) -> Migration {
+        let default = Migration::default();
+        let topology = if config["topology"] == "Full" {
+            Topology::Full
+        } else if config["topology"] == "Random" {
+            Topology::Random
+        } else {
+            Topology::Ring
+        };
+        Migration {
+            interval: config["interval"].as_usize().unwrap_or(default.interval),
+            migrants: config["migrants"].as_usize().unwrap_or(default.migrants),
+            topology,
+        }
+    }
+
+    pub fn due(&self, generation: usize) -> bool {
+        self.interval > 0 && generation.is_multiple_of(self.interval)
+    }
+
+    // Islands receiving the migrants of the given one
+    pub fn targets(&self, island: usize, no_islands: usize, rng: &mut StdRng) -> Vec<usize> {
+        if no_islands < 2 {
+            return vec![];
+        }
+        match self.topology {
+            Topology::Ring => vec![(island + 1) % no_islands],
+            Topology::Full => (0..no_islands).filter(|other| *other != island).collect(),
+            Topology::Random => vec![(island + rng.gen_range(1..no_islands)) % no_islands],
+        }
+    }
+}
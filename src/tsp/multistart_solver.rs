@@ -1,36 +1,51 @@
 use crate::tsp::def::{TSPSolution, TSPInstance};
-use crate::traits::{Solver, Instance};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use crate::traits::{Solver, Instance, Cost};
+use std::thread;
 
 pub struct MultiStartSolver {
     sub_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
-    no_iterations: usize
+    no_iterations: usize,
+    threads: usize
 }
 
 
 impl MultiStartSolver {
-    pub fn new(sub_solver: Box<dyn Solver<TSPInstance, TSPSolution>>, no_iterations: usize) -> MultiStartSolver {
+    pub fn new(sub_solver: Box<dyn Solver<TSPInstance, TSPSolution>>, no_iterations: usize, threads: usize) -> MultiStartSolver {
         MultiStartSolver {
             sub_solver,
-            no_iterations
+            no_iterations,
+            threads
         }
     }
 }
 
 impl Solver<TSPInstance, TSPSolution> for MultiStartSolver {
-    fn solve(&self, start_vertex: usize, instance: &TSPInstance) -> TSPSolution {
-        let mut solution = self.sub_solver.solve(start_vertex, instance);
-        let mut best_solution: (f32, TSPSolution) = (instance.eval(&solution), solution);
-        for i in 0..self.no_iterations - 1 {
-            let mut it_solution = self.sub_solver.solve(start_vertex, instance);
-            let mut it_score = instance.eval(&it_solution);
-            if it_score < best_solution.0 {
-                best_solution = (it_score, it_solution)
-            }
-        }
-        best_solution.1
+    fn solve(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng) -> TSPSolution {
+        // every start is seeded up front, so the result does not depend on how the starts are spread over threads
+        let seeds: Vec<u64> = (0..self.no_iterations).map(|_| rng.gen()).collect();
+        let threads = self.threads.min(self.no_iterations).max(1);
+        let best: Vec<(Cost, usize, TSPSolution)> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads).map(|t| {
+                let seeds = &seeds;
+                scope.spawn(move || {
+                    (t..seeds.len()).step_by(threads).map(|i| {
+                        let solution = self.sub_solver.solve(start_vertex, instance, &mut StdRng::seed_from_u64(seeds[i]));
+                        (instance.eval(&solution), i, solution)
+                    }).min_by_key(|(score, i, _)| (*score, *i)).unwrap()
+                })
+            }).collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+        best.into_iter().min_by_key(|(score, i, _)| (*score, *i)).unwrap().2
     }
 
-    fn solve_s(&self, start_vertex: usize, instance: &TSPInstance, solution: TSPSolution) -> TSPSolution {
+    fn solve_s(&self, _start_vertex: usize, _instance: &TSPInstance, _solution: TSPSolution, _rng: &mut StdRng) -> TSPSolution {
         unimplemented!()
     }
-}
\ No newline at end of file
+
+    fn supports(&self, instance: &TSPInstance) -> bool {
+        self.sub_solver.supports(instance)
+    }
+}
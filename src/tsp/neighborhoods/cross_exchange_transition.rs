@@ -0,0 +1,201 @@
+use crate::traits::Cost;
+use crate::tsp::neighborhoods::transition::Transition;
+use crate::tsp::def::{TSPInstance, TSPSolution, SHARED};
+
+const ORIENTATIONS: usize = 4;
+
+// Exchanges a segment of up to max_length consecutive vertices of one cycle with a segment of another cycle,
+// each of them is put into the other cycle either as it is or reversed
+pub struct CrossExchangeTransition {
+    max_length: usize,
+}
+
+// Segments perm_a[start_a..start_a+length_a] and perm_b[start_b..start_b+length_b],
+// reversed_a tells whether the first one is reversed when it is put into cycle_b and reversed_b the other way round
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Exchange {
+    pub cycle_a: usize,
+    pub start_a: usize,
+    pub length_a: usize,
+    pub cycle_b: usize,
+    pub start_b: usize,
+    pub length_b: usize,
+    pub reversed_a: bool,
+    pub reversed_b: bool,
+}
+
+impl CrossExchangeTransition {
+    pub fn new() -> CrossExchangeTransition {
+        CrossExchangeTransition {
+            max_length: 3,
+        }
+    }
+
+    pub fn with_max_length(max_length: usize) -> CrossExchangeTransition {
+        CrossExchangeTransition {
+            max_length,
+        }
+    }
+
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    // Segments that leave at least one vertex in a cycle of n vertices, they do not wrap around its end
+    fn segments(&self, n: usize) -> usize {
+        (1..=self.max_length.min(n.saturating_sub(1))).map(|length| n - length + 1).sum()
+    }
+
+    // (start, length) of the given segment, shorter segments first
+    fn unpack_segment(&self, segment: usize, n: usize) -> (usize, usize) {
+        let mut segment = segment;
+        let mut length = 1;
+        while segment > n - length {
+            segment -= n - length + 1;
+            length += 1;
+        }
+        (segment, length)
+    }
+
+    pub fn unpack_state(&self, state: usize, solution: &TSPSolution) -> Option<Exchange> {
+        let mut state = state;
+        state-=1;
+        let k = solution.perms.len();
+        for cycle_a in 0..k {
+            for cycle_b in cycle_a+1..k {
+                let (n_a, n_b) = (solution.perms[cycle_a].len(), solution.perms[cycle_b].len());
+                let (segments_a, segments_b) = (self.segments(n_a), self.segments(n_b));
+                if segments_a*segments_b*ORIENTATIONS > state {
+                    let orientation = state%ORIENTATIONS;
+                    let state = state/ORIENTATIONS;
+                    let (start_a, length_a) = self.unpack_segment(state/segments_b, n_a);
+                    let (start_b, length_b) = self.unpack_segment(state%segments_b, n_b);
+                    return Some(Exchange {
+                        cycle_a, start_a, length_a, cycle_b, start_b, length_b,
+                        reversed_a: orientation & 1 == 1,
+                        reversed_b: orientation & 2 == 2,
+                    });
+                }
+                state -= segments_a*segments_b*ORIENTATIONS;
+            }
+        }
+        None
+    }
+
+    // Length of the path from before through the segment to after
+    fn path_cost(&self, before: usize, segment: &[usize], reversed: bool, after: usize, instance: &TSPInstance) -> Cost {
+        let mut cost = 0;
+        let mut last = before;
+        let mut walk = |vertex: usize| {
+            cost += instance.dist_k(last, vertex);
+            last = vertex;
+        };
+        if reversed {
+            segment.iter().rev().for_each(|vertex| walk(*vertex));
+        } else {
+            segment.iter().for_each(|vertex| walk(*vertex));
+        }
+        cost + instance.dist_k(last, after)
+    }
+
+    pub fn score_explicit(&self, exchange: &Exchange, instance: &TSPInstance, solution: &TSPSolution) -> Option<Cost> {
+        let Exchange { cycle_a, start_a, length_a, cycle_b, start_b, length_b, reversed_a, reversed_b } = *exchange;
+        let (perm_a, perm_b) = (&solution.perms[cycle_a], &solution.perms[cycle_b]);
+        let (n_a, n_b) = (perm_a.len(), perm_b.len());
+        if cycle_a == cycle_b || length_a == 0 || length_b == 0 || length_a >= n_a || length_b >= n_b
+            || start_a + length_a > n_a || start_b + length_b > n_b {
+            return None
+        }
+        let (segment_a, segment_b) = (&perm_a[start_a..start_a+length_a], &perm_b[start_b..start_b+length_b]);
+        if segment_a.iter().chain(segment_b.iter()).any(|vertex| solution.cycle[*vertex] == SHARED) {
+            return None
+        }
+        let change = length_b as isize - length_a as isize;
+        let (load_a, load_b) = (instance.load(segment_a), instance.load(segment_b));
+        if !instance.balance.allows_changes(&solution.perms, &[(cycle_a, change), (cycle_b, -change)]) {
+            return None
+        }
+        let (total_a, total_b) = (instance.load(perm_a), instance.load(perm_b));
+        let overload = instance.overload_change(&[(total_a, total_a - load_a + load_b), (total_b, total_b - load_b + load_a)]);
+        let (prev_a, next_a) = (perm_a[(start_a+n_a-1)%n_a], perm_a[(start_a+length_a)%n_a]);
+        let (prev_b, next_b) = (perm_b[(start_b+n_b-1)%n_b], perm_b[(start_b+length_b)%n_b]);
+        let delta = self.path_cost(prev_a, segment_b, reversed_b, next_a, instance)
+            + self.path_cost(prev_b, segment_a, reversed_a, next_b, instance)
+            - self.path_cost(prev_a, segment_a, false, next_a, instance)
+            - self.path_cost(prev_b, segment_b, false, next_b, instance)
+            + overload;
+        Some(delta)
+    }
+
+    pub fn apply_explicit(&self, exchange: &Exchange, solution: &mut TSPSolution) {
+        let Exchange { cycle_a, start_a, length_a, cycle_b, start_b, length_b, reversed_a, reversed_b } = *exchange;
+        let mut segment_a: Vec<usize> = solution.perms[cycle_a][start_a..start_a+length_a].to_vec();
+        let mut segment_b: Vec<usize> = solution.perms[cycle_b][start_b..start_b+length_b].to_vec();
+        if reversed_a {
+            segment_a.reverse();
+        }
+        if reversed_b {
+            segment_b.reverse();
+        }
+        for vertex in segment_a.iter() {
+            solution.cycle[*vertex] = cycle_b;
+        }
+        for vertex in segment_b.iter() {
+            solution.cycle[*vertex] = cycle_a;
+        }
+        solution.perms[cycle_a].splice(start_a..start_a+length_a, segment_b);
+        solution.perms[cycle_b].splice(start_b..start_b+length_b, segment_a);
+        for (cycle, start) in [(cycle_a, start_a), (cycle_b, start_b)] {
+            for i in start..solution.perms[cycle].len() {
+                solution.order[solution.perms[cycle][i]] = i;
+            }
+        }
+    }
+}
+
+impl Default for CrossExchangeTransition {
+    fn default() -> Self {
+        CrossExchangeTransition::new()
+    }
+}
+
+impl Transition for CrossExchangeTransition {
+
+    fn size(&self, solution: &TSPSolution) -> usize {
+        let mut size = 0;
+        for cycle_a in 0..solution.perms.len() {
+            for cycle_b in cycle_a+1..solution.perms.len() {
+                size += self.segments(solution.perms[cycle_a].len())*self.segments(solution.perms[cycle_b].len())*ORIENTATIONS;
+            }
+        }
+        size
+    }
+
+    fn score(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<Cost> {
+        self.score_explicit(&self.unpack_state(state, solution)?, instance, solution)
+    }
+
+    fn apply(&self, state: usize, solution: &mut TSPSolution) {
+        let exchange = self.unpack_state(state, solution).unwrap();
+        self.apply_explicit(&exchange, solution)
+    }
+
+    fn touched(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Vec<usize> {
+        let Exchange { cycle_a, start_a, length_a, cycle_b, start_b, length_b, reversed_a, reversed_b } = self.unpack_state(state, solution).unwrap();
+        let mut touched = vec![];
+        for (cycle, start, length, reversed) in [(cycle_a, start_a, length_a, reversed_a), (cycle_b, start_b, length_b, reversed_b)] {
+            let perm = &solution.perms[cycle];
+            let n = perm.len();
+            touched.extend([perm[(start+n-1)%n], perm[start], perm[start+length-1], perm[(start+length)%n]]);
+            // arcs inside a reversed segment change direction as well
+            if reversed && !instance.symmetric {
+                touched.extend(perm[start..start+length].iter());
+            }
+        }
+        touched
+    }
+
+    fn show_state(&self, state: usize, solution: &TSPSolution) {
+        println!("{:?}", self.unpack_state(state, solution));
+    }
+}
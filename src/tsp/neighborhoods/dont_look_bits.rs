@@ -0,0 +1,120 @@
+use std::collections::VecDeque;
+use rand::seq::SliceRandom;
+use rand::rngs::StdRng;
+
+use crate::traits::Cost;
+use crate::tsp::def::{TSPInstance, TSPSolution};
+use crate::tsp::neighborhoods::neighborhood::Neighborhood;
+use crate::tsp::neighborhoods::transition::Transition;
+
+// Local search that only tries the moves making a vertex adjacent to one of its nearest neighbors.
+// A vertex whose moves gave no improvement is not looked at again until one of its edges changes.
+pub struct DontLookBits {
+    num_neighbors: usize,
+    steepest: bool,
+}
+
+impl DontLookBits {
+    pub fn new(num_neighbors: usize, steepest: bool) -> DontLookBits {
+        DontLookBits {
+            num_neighbors,
+            steepest,
+        }
+    }
+
+    // Transitions that cannot list the moves around a vertex are scanned in full once no vertex is left to look at
+    pub fn search(&self, transitions: Vec<Box<dyn Transition>>, instance: &TSPInstance, mut solution: TSPSolution, rng: &mut StdRng) -> TSPSolution {
+        let neighbors = instance.nearest_neighbors(self.num_neighbors);
+        let mut listed = vec![];
+        let mut scanned = vec![];
+        for transition in transitions {
+            if transition.has_candidates() {
+                listed.push(transition);
+            } else {
+                scanned.push(transition);
+            }
+        }
+        let mut neighborhood = if scanned.is_empty() {
+            None
+        } else {
+            Some(Neighborhood::new(scanned, &solution, !self.steepest, rng))
+        };
+        let mut vertices: Vec<usize> = (0..instance.dimension).filter(|vertex| solution.is_exclusive(*vertex)).collect();
+        if !self.steepest {
+            vertices.shuffle(rng);
+        }
+        let mut looking = vec![false; instance.dimension];
+        for vertex in vertices.iter() {
+            looking[*vertex] = true;
+        }
+        let mut queue: VecDeque<usize> = vertices.into();
+        loop {
+            while let Some(vertex) = queue.pop_front() {
+                looking[vertex] = false;
+                if !solution.is_exclusive(vertex) {
+                    continue;
+                }
+                if let Some((transition, state)) = self.move_around(vertex, &listed, &neighbors[vertex], instance, &solution) {
+                    let touched = listed[transition].touched(state, instance, &solution);
+                    listed[transition].apply(state, &mut solution);
+                    self.wake(&touched, &solution, &mut looking, &mut queue);
+                }
+            }
+            let neighborhood = match neighborhood.as_mut() {
+                Some(neighborhood) => neighborhood,
+                None => break,
+            };
+            match self.scan(neighborhood, instance, &solution, rng) {
+                Some(state) => {
+                    let touched = neighborhood.touched(state, instance, &solution);
+                    neighborhood.apply_transition(state, &mut solution);
+                    self.wake(&touched, &solution, &mut looking, &mut queue);
+                }
+                None => break,
+            }
+        }
+        solution
+    }
+
+    // The first improving move around the vertex, or the best one for the steepest search
+    fn move_around(&self, vertex: usize, transitions: &[Box<dyn Transition>], neighbors: &[usize], instance: &TSPInstance, solution: &TSPSolution) -> Option<(usize, usize)> {
+        let mut best: (Cost, Option<(usize, usize)>) = (0, None);
+        for (i, transition) in transitions.iter().enumerate() {
+            for state in transition.candidates(vertex, neighbors, solution) {
+                if let Some(score) = transition.score(state, instance, solution) {
+                    if score < best.0 {
+                        best = (score, Some((i, state)));
+                        if !self.steepest {
+                            return best.1;
+                        }
+                    }
+                }
+            }
+        }
+        best.1
+    }
+
+    fn scan(&self, neighborhood: &mut Neighborhood, instance: &TSPInstance, solution: &TSPSolution, rng: &mut StdRng) -> Option<usize> {
+        neighborhood.reset(solution, rng);
+        let mut best: (Cost, Option<usize>) = (0, None);
+        while let Some((score, state)) = neighborhood.next(instance, solution) {
+            if score < best.0 {
+                best = (score, Some(state));
+                if !self.steepest {
+                    break;
+                }
+            }
+        }
+        best.1
+    }
+
+    // Clears the bits of the vertices whose edges were changed by the last move
+    fn wake(&self, touched: &[usize], solution: &TSPSolution, looking: &mut [bool], queue: &mut VecDeque<usize>) {
+        for vertex in touched.iter().cloned() {
+            if solution.is_exclusive(vertex) && !looking[vertex] {
+                looking[vertex] = true;
+                queue.push_back(vertex);
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use crate::tsp::def::TSPSolution;
+use crate::traits::Cost;
+use crate::tsp::def::{TSPSolution, SHARED};
 use crate::tsp::def::TSPInstance;
 use crate::tsp::neighborhoods::transition::Transition;
 
@@ -11,47 +12,61 @@ impl EdgesTransition {
     pub fn unpack_state(&self, state: usize, solution: &TSPSolution) -> Option<(usize, usize, usize)> {
         let mut state = state;
         state-=1;
-        let (n_a, n_b) = (solution.perm_a.len(), solution.perm_b.len());
-        if n_a*(n_a-3) > state { //first cycle
-            Some((0, state/(n_a-3), state%(n_a-3)))
-        } else if n_a*(n_a-3) + n_b*(n_b-3) > state {
-            state -= n_a*(n_a-3);
-            Some((1, state/(n_b-3), state%(n_b-3)))
-        } else {
-            return None
+        for (cycle, perm) in solution.perms.iter().enumerate() {
+            let n = perm.len();
+            let size = n*n.saturating_sub(3);
+            if size > state {
+                return Some((cycle, state/(n-3), state%(n-3)))
+            }
+            state -= size;
         }
+        None
     }
 
     fn pack_state(&self, cycle: usize, vertex_a: usize, vertex_b: usize, solution: &TSPSolution) -> usize {
-        // println!("{} {} {}", cycle, vertex_a, vertex_b);
-        let mut vertex_a = vertex_a;
-        let mut shift = (vertex_b as i32)-(vertex_a as i32)-2;
-        let (n_a, n_b) = (solution.perm_a.len(), solution.perm_b.len());
-        let mut state: usize;
-        if cycle == 0 {
-            vertex_a = (vertex_a+1)%n_a;
-            if shift < 0 {
-                shift+=n_a as i32;
-            }
-            shift = (shift%n_a as i32 + n_a as i32)%n_a as i32;
-            state = (n_a-3)*vertex_a + (shift as usize);
-        } else {
-            vertex_a = (vertex_a+1)%n_b;
-            if shift < 0 {
-                shift+=n_b as i32;
-            }
-            shift = (shift%n_b as i32 + n_b as i32)%n_b as i32;
-            state = (n_a-3)*n_a + (n_b-3)*vertex_a + (shift as usize);
-        }
+        let mut state: usize = solution.perms[..cycle].iter()
+            .map(|perm| perm.len()*perm.len().saturating_sub(3))
+            .sum();
+        let n = solution.perms[cycle].len();
+        let vertex_a = (vertex_a+1)%n;
+        let mut shift = (vertex_b as i32)-(vertex_a as i32)-1;
+        shift = (shift%n as i32 + n as i32)%n as i32;
+        state += (n-3)*vertex_a + (shift as usize);
         state+=1;
         state
     }
 
+    // Positions of the segment apply reverses, either the one between the removed edges or its complement
+    fn reversed_range(&self, vertex: usize, shift: usize, perm: &[usize], solution: &TSPSolution) -> (usize, usize) {
+        let a = vertex;
+        let b = (a+shift+1)%perm.len();
+        if b < a {
+            (b+1, a-1)
+        } else if a == 0 && solution.cycle[perm[0]] == SHARED {
+            // reversing the complementary segment gives the same cycle and leaves the depot in front
+            (b+1, perm.len()-1)
+        } else {
+            (a, b)
+        }
+    }
+
+    // Change of length after reversing perm[begin..=end], arcs inside the segment change direction
+    fn reversal_delta(&self, begin: usize, end: usize, perm: &[usize], instance: &TSPInstance) -> Cost {
+        let n = perm.len();
+        let (prev, next) = (perm[(begin+n-1)%n], perm[(end+1)%n]);
+        let mut delta = instance.dist_k(prev, perm[end]) + instance.dist_k(perm[begin], next)
+            - instance.dist_k(prev, perm[begin]) - instance.dist_k(perm[end], next);
+        for i in begin..end {
+            delta += instance.dist_k(perm[i+1], perm[i]) - instance.dist_k(perm[i], perm[i+1]);
+        }
+        delta
+    }
+
     pub fn apply_explicit(&self, cycle: usize, vertex_a: usize, vertex_b: usize, solution: &mut TSPSolution) {
         self.apply(self.pack_state(cycle, vertex_a, vertex_b, solution), solution);
     }
 
-    pub fn score_explicit(&self, cycle: usize, vertex_a: usize, vertex_b: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<f32> {
+    pub fn score_explicit(&self, cycle: usize, vertex_a: usize, vertex_b: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<Cost> {
         self.score(self.pack_state(cycle, vertex_a, vertex_b, solution), instance, solution)
     }
 }
@@ -59,20 +74,23 @@ impl EdgesTransition {
 impl Transition for EdgesTransition {
 
     fn size(&self, solution: &TSPSolution) -> usize {
-        let (n_a, n_b) = (solution.perm_a.len(), solution.perm_b.len());
-        n_a*(n_a-3) + n_b*(n_b-3)
+        solution.perms.iter().map(|perm| perm.len()*perm.len().saturating_sub(3)).sum()
     }
 
-    fn score(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<f32> {
+    fn score(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<Cost> {
         let st = self.unpack_state(state, solution);
         if st.is_none() {
             return None
         }
         let (cycle, vertex, shift) = st.unwrap();
-        let perm = vec![&solution.perm_a, &solution.perm_b];
+        let perm = &solution.perms;
+        if !instance.symmetric {
+            let (begin, end) = self.reversed_range(vertex, shift, &perm[cycle], solution);
+            return Some(self.reversal_delta(begin, end, &perm[cycle], instance));
+        }
         let vertex_b = (vertex+shift+1)%perm[cycle].len();
-        let (a_prev, a, _) = self.get_neighbors_in_cycle(vertex, perm[cycle]);
-        let (_, b, b_next) = self.get_neighbors_in_cycle(vertex_b, perm[cycle]);
+        let (a_prev, a, _) = self.get_neighbors_in_cycle(vertex, &perm[cycle]);
+        let (_, b, b_next) = self.get_neighbors_in_cycle(vertex_b, &perm[cycle]);
         let delta = instance.dist_k(a, b_next) + instance.dist_k(a_prev, b)
             - instance.dist_k(a_prev, a) - instance.dist_k(b, b_next);
         Some(delta)
@@ -80,14 +98,8 @@ impl Transition for EdgesTransition {
 
     fn apply(&self, state: usize, solution: &mut TSPSolution) {
         let (cycle, vertex, shift) = self.unpack_state(state, solution).unwrap();
-        let perm = if cycle == 0 { &mut solution.perm_a } else { &mut solution.perm_b };
-        let mut a = vertex;
-        let mut b = (a+shift+1)%perm.len();
-        if b < a {
-            b+=1;
-            a-=1;
-            let t = b; b = a; a = t;
-        }
+        let (mut a, mut b) = self.reversed_range(vertex, shift, &solution.perms[cycle], solution);
+        let perm = &mut solution.perms[cycle];
         while a < b {
             let vertex_a = perm[a];
             let vertex_b = perm[b];
@@ -104,7 +116,45 @@ impl Transition for EdgesTransition {
             b-=1;
         }
     }
+    fn touched(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Vec<usize> {
+        let (cycle, vertex, shift) = self.unpack_state(state, solution).unwrap();
+        let perm = &solution.perms[cycle];
+        let n = perm.len();
+        let (begin, end) = self.reversed_range(vertex, shift, perm, solution);
+        // arcs inside the reversed segment change direction as well
+        if instance.symmetric {
+            vec![perm[(begin+n-1)%n], perm[begin], perm[end], perm[(end+1)%n]]
+        } else {
+            (0..end+3-begin).map(|offset| perm[(begin+n-1+offset)%n]).collect()
+        }
+    }
+
     fn show_state(&self, state: usize, solution: &TSPSolution) {
         println!("{:?}", self.unpack_state(state, solution));
     }
+
+    fn has_candidates(&self) -> bool {
+        true
+    }
+
+    // Adds the edge between vertex and a neighbor in its cycle together with the one between their successors
+    // or the one between their predecessors
+    fn candidates(&self, vertex: usize, neighbors: &[usize], solution: &TSPSolution) -> Vec<usize> {
+        let cycle = solution.cycle[vertex];
+        let n = solution.perms[cycle].len();
+        let mut states = vec![];
+        if n < 4 {
+            return states;
+        }
+        let i = solution.order[vertex];
+        for neighbor in neighbors.iter().filter(|neighbor| solution.cycle[**neighbor] == cycle) {
+            let j = solution.order[*neighbor];
+            for (a, b) in [(i, j), ((i+n-1)%n, (j+n-1)%n)] {
+                if (b+n-a)%n > 1 && (a+n-b)%n > 1 {
+                    states.push(self.pack_state(cycle, a, b, solution));
+                }
+            }
+        }
+        states
+    }
 }
\ No newline at end of file
@@ -0,0 +1,76 @@
+use crate::traits::Cost;
+use crate::tsp::neighborhoods::transition::Transition;
+use crate::tsp::def::{TSPInstance, TSPSolution, UNVISITED};
+
+pub struct InsertTransition {}
+
+impl InsertTransition {
+    pub fn new() -> InsertTransition {
+        InsertTransition {}
+    }
+
+    // (vertex, cycle, position the vertex is inserted at)
+    pub fn unpack_state(&self, state: usize, solution: &TSPSolution) -> Option<(usize, usize, usize)> {
+        let total: usize = solution.perms.iter().map(|perm| perm.len()).sum();
+        if total == 0 {
+            return None
+        }
+        let state = state-1;
+        let vertex = state/total;
+        let mut position = state%total;
+        for (cycle, perm) in solution.perms.iter().enumerate() {
+            if perm.len() > position {
+                return Some((vertex, cycle, position))
+            }
+            position -= perm.len();
+        }
+        None
+    }
+}
+
+impl Default for InsertTransition {
+    fn default() -> Self {
+        InsertTransition::new()
+    }
+}
+
+impl Transition for InsertTransition {
+
+    fn size(&self, solution: &TSPSolution) -> usize {
+        solution.cycle.len()*solution.perms.iter().map(|perm| perm.len()).sum::<usize>()
+    }
+
+    fn score(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<Cost> {
+        let (vertex, cycle, position) = self.unpack_state(state, solution)?;
+        if solution.cycle[vertex] != UNVISITED
+            || !instance.balance.allows_changes(&solution.perms, &[(cycle, 1)]) {
+            return None
+        }
+        let (prev, next, _) = self.get_neighbors_in_cycle(position, &solution.perms[cycle]);
+        let load = instance.load(&solution.perms[cycle]);
+        let delta = instance.dist_k(prev, vertex) + instance.dist_k(vertex, next) - instance.dist_k(prev, next)
+            - instance.prize(vertex) + instance.overload_change(&[(load, load + instance.demand(vertex))]);
+        Some(delta)
+    }
+
+    fn apply(&self, state: usize, solution: &mut TSPSolution) {
+        let (vertex, cycle, position) = self.unpack_state(state, solution).unwrap();
+        // inserting in front of the first vertex closes the cycle the same way as appending
+        let position = if position == 0 { solution.perms[cycle].len() } else { position };
+        solution.perms[cycle].insert(position, vertex);
+        solution.cycle[vertex] = cycle;
+        for i in position..solution.perms[cycle].len() {
+            solution.order[solution.perms[cycle][i]] = i;
+        }
+    }
+
+    fn touched(&self, state: usize, _instance: &TSPInstance, solution: &TSPSolution) -> Vec<usize> {
+        let (vertex, cycle, position) = self.unpack_state(state, solution).unwrap();
+        let (prev, next, _) = self.get_neighbors_in_cycle(position, &solution.perms[cycle]);
+        vec![prev, vertex, next]
+    }
+
+    fn show_state(&self, state: usize, solution: &TSPSolution) {
+        println!("{:?}", self.unpack_state(state, solution));
+    }
+}
@@ -1,5 +1,6 @@
+use crate::traits::Cost;
 use crate::tsp::neighborhoods::transition::Transition;
-use crate::tsp::def::{TSPInstance, TSPSolution};
+use crate::tsp::def::{TSPInstance, TSPSolution, SHARED};
 
 pub struct InterCycleTransition {}
 
@@ -7,72 +8,123 @@ impl InterCycleTransition {
     pub fn new() -> InterCycleTransition {
         InterCycleTransition {}
     }
-    pub fn unpack_state(&self, state: usize, solution: &TSPSolution) -> Option<(usize, usize)> {
+    pub fn unpack_state(&self, state: usize, solution: &TSPSolution) -> Option<(usize, usize, usize, usize)> {
         let mut state = state;
         state-=1;
-        let (n_a, n_b) = (solution.perm_a.len(), solution.perm_b.len());
-        let cycles = (state/n_b, state%n_b);
-        if cycles.0 >= n_a {
-            return None;
+        let k = solution.perms.len();
+        for cycle_a in 0..k {
+            for cycle_b in cycle_a+1..k {
+                let (n_a, n_b) = (solution.perms[cycle_a].len(), solution.perms[cycle_b].len());
+                if n_a*n_b > state {
+                    return Some((cycle_a, state/n_b, cycle_b, state%n_b));
+                }
+                state -= n_a*n_b;
+            }
         }
-        Some(cycles)
+        None
     }
-    fn pack_state(&self, cycle_a: usize, cycle_b: usize, solution: &TSPSolution) -> usize {
-        let (_, n_b) = (solution.perm_a.len(), solution.perm_b.len());
-        cycle_b + cycle_a*n_b + 1
+    fn pack_state(&self, cycle_a: usize, id_a: usize, cycle_b: usize, id_b: usize, solution: &TSPSolution) -> usize {
+        let ((cycle_a, id_a), (cycle_b, id_b)) = if cycle_a < cycle_b {
+            ((cycle_a, id_a), (cycle_b, id_b))
+        } else {
+            ((cycle_b, id_b), (cycle_a, id_a))
+        };
+        let mut state = 0;
+        for c_a in 0..cycle_a+1 {
+            for c_b in c_a+1..solution.perms.len() {
+                if c_a == cycle_a && c_b == cycle_b {
+                    return state + id_b + id_a*solution.perms[cycle_b].len() + 1;
+                }
+                state += solution.perms[c_a].len()*solution.perms[c_b].len();
+            }
+        }
+        panic!("Cycles {} and {} cannot be swapped", cycle_a, cycle_b)
     }
 
-    pub fn apply_explicit(&self, cycle_a: usize, cycle_b: usize, solution: &mut TSPSolution) {
-        self.apply(self.pack_state(cycle_a, cycle_b, solution), solution)
+    pub fn apply_explicit(&self, cycle_a: usize, id_a: usize, cycle_b: usize, id_b: usize, solution: &mut TSPSolution) {
+        self.apply(self.pack_state(cycle_a, id_a, cycle_b, id_b, solution), solution)
     }
 
-    pub fn score_explicit(&self, cycle_a: usize, cycle_b: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<f32> {
-        self.score(self.pack_state(cycle_a, cycle_b, solution), instance, solution)
+    pub fn score_explicit(&self, cycle_a: usize, id_a: usize, cycle_b: usize, id_b: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<Cost> {
+        self.score(self.pack_state(cycle_a, id_a, cycle_b, id_b, solution), instance, solution)
     }
 }
 
 impl Transition for InterCycleTransition {
 
     fn size(&self, solution: &TSPSolution) -> usize {
-        let (n_a, n_b) = (solution.perm_a.len(), solution.perm_b.len());
-        n_a*n_b
+        let mut size = 0;
+        for cycle_a in 0..solution.perms.len() {
+            for cycle_b in cycle_a+1..solution.perms.len() {
+                size += solution.perms[cycle_a].len()*solution.perms[cycle_b].len();
+            }
+        }
+        size
     }
 
-    fn score(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<f32> {
+    fn score(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<Cost> {
         let ids = self.unpack_state(state, &solution);
         if ids.is_none() {
             return None
         }
-        let (cycle_a, cycle_b) = ids.unwrap();
-        let (a_prev, a, a_next) = self.get_neighbors_in_cycle(cycle_a, &solution.perm_a);
-        let (b_prev, b, b_next) = self.get_neighbors_in_cycle(cycle_b, &solution.perm_b);
+        let (cycle_a, id_a, cycle_b, id_b) = ids.unwrap();
+        let (a_prev, a, a_next) = self.get_neighbors_in_cycle(id_a, &solution.perms[cycle_a]);
+        let (b_prev, b, b_next) = self.get_neighbors_in_cycle(id_b, &solution.perms[cycle_b]);
+        if solution.cycle[a] == SHARED || solution.cycle[b] == SHARED {
+            return None
+        }
         let delta = instance.dist_k(b_prev, a) + instance.dist_k(a, b_next)
             + instance.dist_k(a_prev, b) + instance.dist_k(b, a_next)
             - instance.dist_k(a_prev, a) - instance.dist_k(a, a_next)
-            - instance.dist_k(b_prev, b) - instance.dist_k(b, b_next);
+            - instance.dist_k(b_prev, b) - instance.dist_k(b, b_next)
+            + instance.swap_overload_change(solution, a, b);
         Some(delta)
     }
 
     fn apply(&self, state: usize, solution: &mut TSPSolution) {
-        let (cycle_a, cycle_b) = self.unpack_state(state, solution).unwrap();
-        let vertex_a = solution.perm_a[cycle_a];
-        let vertex_b = solution.perm_b[cycle_b];
+        let (cycle_a, id_a, cycle_b, id_b) = self.unpack_state(state, solution).unwrap();
+        let vertex_a = solution.perms[cycle_a][id_a];
+        let vertex_b = solution.perms[cycle_b][id_b];
 
         assert_ne!(solution.cycle[vertex_a], solution.cycle[vertex_b]);
 
-        solution.cycle[vertex_a]^=1;
-        solution.cycle[vertex_b]^=1;
+        solution.cycle[vertex_a] = cycle_b;
+        solution.cycle[vertex_b] = cycle_a;
 
         let tmp = solution.order[vertex_b];
         solution.order[vertex_b] = solution.order[vertex_a];
         solution.order[vertex_a] = tmp;
 
-        let t = solution.perm_a[cycle_a];
-        solution.perm_a[cycle_a] = solution.perm_b[cycle_b];
-        solution.perm_b[cycle_b] = t;
+        solution.perms[cycle_a][id_a] = vertex_b;
+        solution.perms[cycle_b][id_b] = vertex_a;
     }
-    
+
+    fn touched(&self, state: usize, _instance: &TSPInstance, solution: &TSPSolution) -> Vec<usize> {
+        let (cycle_a, id_a, cycle_b, id_b) = self.unpack_state(state, solution).unwrap();
+        let (a_prev, a, a_next) = self.get_neighbors_in_cycle(id_a, &solution.perms[cycle_a]);
+        let (b_prev, b, b_next) = self.get_neighbors_in_cycle(id_b, &solution.perms[cycle_b]);
+        vec![a_prev, a, a_next, b_prev, b, b_next]
+    }
+
     fn show_state(&self, state: usize, solution: &TSPSolution) {
         println!("{:?}", self.unpack_state(state, solution));
     }
-}
\ No newline at end of file
+
+    fn has_candidates(&self) -> bool {
+        true
+    }
+
+    // vertex takes the place of the predecessor or the successor of a neighbor in another cycle
+    fn candidates(&self, vertex: usize, neighbors: &[usize], solution: &TSPSolution) -> Vec<usize> {
+        let (cycle_a, id_a) = (solution.cycle[vertex], solution.order[vertex]);
+        let mut states = vec![];
+        for neighbor in neighbors.iter().filter(|neighbor| solution.is_exclusive(**neighbor) && solution.cycle[**neighbor] != cycle_a) {
+            let cycle_b = solution.cycle[*neighbor];
+            let n_b = solution.perms[cycle_b].len();
+            for id_b in [(solution.order[*neighbor]+n_b-1)%n_b, (solution.order[*neighbor]+1)%n_b] {
+                states.push(self.pack_state(cycle_a, id_a, cycle_b, id_b, solution));
+            }
+        }
+        states
+    }
+}
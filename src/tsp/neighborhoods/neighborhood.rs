@@ -1,4 +1,6 @@
+use crate::traits::Cost;
 use rand::Rng;
+use rand::rngs::StdRng;
 
 use crate::tsp::def::TSPSolution;
 use crate::tsp::def::TSPInstance;
@@ -19,47 +21,57 @@ pub struct Neighborhood {
 
 impl Neighborhood {
     
-    pub fn new(transitions: Vec<Box<dyn Transition>>, solution: &TSPSolution, random: bool) -> Neighborhood{
-        let mut transition_sizes = Vec::new();
-        let mut neighborhood_size: usize = 0;
-        let mut no_transitions: usize = 0;
-        for transition in &transitions {
-            transition_sizes.push(transition.size(solution));
-            neighborhood_size += transition_sizes.last().unwrap();
-        }
-        let (generator, group_size) = if random {
-            Primes::group_generator_and_size(neighborhood_size)
-        } else {
-            (1, neighborhood_size+1)
-        };
+    pub fn new(transitions: Vec<Box<dyn Transition>>, solution: &TSPSolution, random: bool, rng: &mut StdRng) -> Neighborhood{
         let mut neighborhood = Neighborhood {
-            generator,
-            group_size,
+            generator: 1,
+            group_size: 1,
             random,
             state: 0,
             active: false,
             start_state: 0,    
             transitions,
-            transition_sizes,
-            neighborhood_size
+            transition_sizes: Vec::new(),
+            neighborhood_size: 0
         };
-        neighborhood.reset();
+        neighborhood.reset(solution, rng);
         neighborhood
     }
 
-    pub fn next_random(&mut self) -> usize {
-        let mut next_state = rand::thread_rng().gen_range(1..self.neighborhood_size);
+    // Moves such as relocation change cycle sizes, so the state space has to follow the solution
+    fn resize(&mut self, solution: &TSPSolution, rng: &mut StdRng) {
+        let transition_sizes: Vec<usize> = self.transitions.iter()
+            .map(|transition| transition.size(solution))
+            .collect();
+        if transition_sizes == self.transition_sizes {
+            return;
+        }
+        let neighborhood_size: usize = transition_sizes.iter().sum();
+        if neighborhood_size != self.neighborhood_size || self.transition_sizes.is_empty() {
+            let (generator, group_size) = if self.random {
+                Primes::group_generator_and_size(neighborhood_size, rng)
+            } else {
+                (1, neighborhood_size+1)
+            };
+            self.generator = generator;
+            self.group_size = group_size;
+        }
+        self.transition_sizes = transition_sizes;
+        self.neighborhood_size = neighborhood_size;
+    }
+
+    pub fn next_random(&mut self, rng: &mut StdRng) -> usize {
+        let next_state = rng.gen_range(1..=self.neighborhood_size);
         self.state = next_state;
         next_state
     }
 
-    pub fn next(&mut self, instance: &TSPInstance, solution: &TSPSolution) -> Option<(f32, usize)> {
+    pub fn next(&mut self, instance: &TSPInstance, solution: &TSPSolution) -> Option<(Cost, usize)> {
         loop {
             if self.active && self.state == self.start_state {
                 return None;
             }
             self.active = true;
-            let mut score: Option<f32> = None;
+            let mut score: Option<Cost> = None;
             let mut current_state = self.state;
             for i in 0..self.transitions.len() {
                 if current_state <= self.transition_sizes[i] {
@@ -84,6 +96,17 @@ impl Neighborhood {
         }
     }
     
+    pub fn score(&self, transition: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<Cost> {
+        let mut current_state = transition;
+        for i in 0..self.transitions.len() {
+            if current_state <= self.transition_sizes[i] {
+                return self.transitions[i].score(current_state, instance, solution);
+            }
+            current_state-=self.transition_sizes[i];
+        }
+        None
+    }
+
     pub fn apply_transition(&self, transition: usize, solution: &mut TSPSolution) {
         let mut current_state = transition;
         for i in 0..self.transitions.len() {
@@ -96,6 +119,17 @@ impl Neighborhood {
         }
     }
 
+    pub fn touched(&self, transition: usize, instance: &TSPInstance, solution: &TSPSolution) -> Vec<usize> {
+        let mut current_state = transition;
+        for i in 0..self.transitions.len() {
+            if current_state <= self.transition_sizes[i] {
+                return self.transitions[i].touched(current_state, instance, solution);
+            }
+            current_state-=self.transition_sizes[i];
+        }
+        Vec::new()
+    }
+
     pub fn show_transition(&self, transition: usize, solution: &TSPSolution) {
         let mut current_state = transition;
         for i in 0..self.transitions.len() {
@@ -107,9 +141,9 @@ impl Neighborhood {
         }
     }
 
-    pub fn reset(&mut self) {
+    pub fn reset(&mut self, solution: &TSPSolution, rng: &mut StdRng) {
+        self.resize(solution, rng);
         if self.random {
-            let mut rng = rand::thread_rng();
             self.state = rng.gen_range(1..self.group_size);
         } else {
             self.state = 1;
@@ -0,0 +1,153 @@
+use crate::traits::Cost;
+use crate::tsp::neighborhoods::transition::Transition;
+use crate::tsp::def::{TSPInstance, TSPSolution, SHARED};
+
+// Moves a chain of up to max_length consecutive vertices between two other vertices of the same or another cycle
+pub struct OrOptTransition {
+    max_length: usize,
+}
+
+impl OrOptTransition {
+    pub fn new() -> OrOptTransition {
+        OrOptTransition {
+            max_length: 3,
+        }
+    }
+
+    pub fn with_max_length(max_length: usize) -> OrOptTransition {
+        OrOptTransition {
+            max_length,
+        }
+    }
+
+    // Insertion points for a chain of the given length taken out of cycle_a, the one it was taken from excluded
+    fn targets(&self, cycle_a: usize, length: usize, solution: &TSPSolution) -> usize {
+        let rest = solution.perms[cycle_a].len() - length;
+        let total: usize = solution.perms.iter().map(|perm| perm.len()).sum();
+        rest.saturating_sub(1) + total - solution.perms[cycle_a].len()
+    }
+
+    // Vertices the chain is inserted between
+    fn insertion_point(&self, cycle_a: usize, length: usize, start: usize, cycle_b: usize, target: usize, solution: &TSPSolution) -> (usize, usize) {
+        if cycle_a == cycle_b {
+            // positions in the cycle with the chain removed
+            let perm_a = &solution.perms[cycle_a];
+            let rest = perm_a.len() - length;
+            let at = |i: usize| if i < start { perm_a[i] } else { perm_a[i+length] };
+            (at((target+rest-1)%rest), at(target))
+        } else {
+            let perm_b = &solution.perms[cycle_b];
+            (perm_b[(target+perm_b.len()-1)%perm_b.len()], perm_b[target])
+        }
+    }
+
+    // (source cycle, chain length, chain start, target cycle, insertion point), the chain is inserted
+    // in front of the vertex at the insertion point of the target cycle with the chain removed, where 0 stands for its end
+    pub fn unpack_state(&self, state: usize, solution: &TSPSolution) -> Option<(usize, usize, usize, usize, usize)> {
+        let mut state = state;
+        state-=1;
+        for (cycle_a, perm) in solution.perms.iter().enumerate() {
+            for length in 1..=self.max_length.min(perm.len().saturating_sub(1)) {
+                let starts = perm.len() - length + 1;
+                let targets = self.targets(cycle_a, length, solution);
+                if starts*targets > state {
+                    let (start, mut target) = (state/targets, state%targets);
+                    let rest = perm.len() - length;
+                    if target < rest.saturating_sub(1) {
+                        if target >= start % rest {
+                            target+=1;
+                        }
+                        return Some((cycle_a, length, start, cycle_a, target));
+                    }
+                    target -= rest.saturating_sub(1);
+                    for (cycle_b, perm_b) in solution.perms.iter().enumerate() {
+                        if cycle_b == cycle_a {
+                            continue;
+                        }
+                        if target < perm_b.len() {
+                            return Some((cycle_a, length, start, cycle_b, target));
+                        }
+                        target -= perm_b.len();
+                    }
+                }
+                state -= starts*targets;
+            }
+        }
+        None
+    }
+}
+
+impl Default for OrOptTransition {
+    fn default() -> Self {
+        OrOptTransition::new()
+    }
+}
+
+impl Transition for OrOptTransition {
+
+    fn size(&self, solution: &TSPSolution) -> usize {
+        (0..solution.perms.len()).map(|cycle_a| {
+            let n = solution.perms[cycle_a].len();
+            (1..=self.max_length.min(n.saturating_sub(1)))
+                .map(|length| (n - length + 1)*self.targets(cycle_a, length, solution))
+                .sum::<usize>()
+        }).sum()
+    }
+
+    fn score(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<Cost> {
+        let (cycle_a, length, start, cycle_b, target) = self.unpack_state(state, solution)?;
+        let perm_a = &solution.perms[cycle_a];
+        let chain = &perm_a[start..start+length];
+        if chain.iter().any(|vertex| solution.cycle[*vertex] == SHARED) {
+            return None
+        }
+        let n = perm_a.len();
+        let (first, last) = (chain[0], chain[length-1]);
+        let (prev, next) = (perm_a[(start+n-1)%n], perm_a[(start+length)%n]);
+        let (before, after) = self.insertion_point(cycle_a, length, start, cycle_b, target, solution);
+        let overload = if cycle_a == cycle_b {
+            0
+        } else {
+            let changes = [(cycle_a, -(length as isize)), (cycle_b, length as isize)];
+            if !instance.balance.allows_changes(&solution.perms, &changes) {
+                return None
+            }
+            let perm_b = &solution.perms[cycle_b];
+            let (load_a, load_b, load) = (instance.load(perm_a), instance.load(perm_b), instance.load(chain));
+            instance.overload_change(&[(load_a, load_a - load), (load_b, load_b + load)])
+        };
+        let delta = instance.dist_k(prev, next) - instance.dist_k(prev, first) - instance.dist_k(last, next)
+            + instance.dist_k(before, first) + instance.dist_k(last, after) - instance.dist_k(before, after)
+            + overload;
+        Some(delta)
+    }
+
+    fn apply(&self, state: usize, solution: &mut TSPSolution) {
+        let (cycle_a, length, start, cycle_b, target) = self.unpack_state(state, solution).unwrap();
+        let chain: Vec<usize> = solution.perms[cycle_a].drain(start..start+length).collect();
+        // inserting in front of the first vertex closes the cycle the same way as appending,
+        // which keeps a shared depot in place
+        let target = if target == 0 { solution.perms[cycle_b].len() } else { target };
+        solution.perms[cycle_b].splice(target..target, chain.iter().cloned());
+        for vertex in chain.iter() {
+            solution.cycle[*vertex] = cycle_b;
+        }
+        for cycle in [cycle_a, cycle_b] {
+            for i in 0..solution.perms[cycle].len() {
+                solution.order[solution.perms[cycle][i]] = i;
+            }
+        }
+    }
+
+    fn touched(&self, state: usize, _instance: &TSPInstance, solution: &TSPSolution) -> Vec<usize> {
+        let (cycle_a, length, start, cycle_b, target) = self.unpack_state(state, solution).unwrap();
+        let perm_a = &solution.perms[cycle_a];
+        let n = perm_a.len();
+        let (before, after) = self.insertion_point(cycle_a, length, start, cycle_b, target, solution);
+        vec![perm_a[(start+n-1)%n], perm_a[start], perm_a[start+length-1], perm_a[(start+length)%n], before, after]
+    }
+
+    fn show_state(&self, state: usize, solution: &TSPSolution) {
+        println!("{:?}", self.unpack_state(state, solution));
+    }
+}
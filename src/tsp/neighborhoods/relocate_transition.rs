@@ -0,0 +1,123 @@
+use crate::traits::Cost;
+use crate::tsp::neighborhoods::transition::Transition;
+use crate::tsp::def::{TSPInstance, TSPSolution, SHARED};
+
+pub struct RelocateTransition {}
+
+impl RelocateTransition {
+    pub fn new() -> RelocateTransition {
+        RelocateTransition {}
+    }
+
+    // (source cycle, position in it, target cycle, position the vertex is inserted at)
+    pub fn unpack_state(&self, state: usize, solution: &TSPSolution) -> Option<(usize, usize, usize, usize)> {
+        let mut state = state;
+        state-=1;
+        let k = solution.perms.len();
+        for cycle_a in 0..k {
+            for cycle_b in 0..k {
+                if cycle_a == cycle_b {
+                    continue;
+                }
+                let (n_a, n_b) = (solution.perms[cycle_a].len(), solution.perms[cycle_b].len());
+                if n_a*n_b > state {
+                    return Some((cycle_a, state/n_b, cycle_b, state%n_b));
+                }
+                state -= n_a*n_b;
+            }
+        }
+        None
+    }
+
+    fn pack_state(&self, cycle_a: usize, id_a: usize, cycle_b: usize, id_b: usize, solution: &TSPSolution) -> usize {
+        let mut state = 0;
+        let k = solution.perms.len();
+        for c_a in 0..k {
+            for c_b in 0..k {
+                if c_a == c_b {
+                    continue;
+                }
+                let (n_a, n_b) = (solution.perms[c_a].len(), solution.perms[c_b].len());
+                if c_a == cycle_a && c_b == cycle_b {
+                    return state + id_a*n_b + id_b + 1;
+                }
+                state += n_a*n_b;
+            }
+        }
+        panic!("Vertices cannot be relocated from cycle {} to cycle {}", cycle_a, cycle_b)
+    }
+}
+
+impl Default for RelocateTransition {
+    fn default() -> Self {
+        RelocateTransition::new()
+    }
+}
+
+impl Transition for RelocateTransition {
+
+    fn size(&self, solution: &TSPSolution) -> usize {
+        let total: usize = solution.perms.iter().map(|perm| perm.len()).sum();
+        solution.perms.iter().map(|perm| perm.len()*(total - perm.len())).sum()
+    }
+
+    fn score(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<Cost> {
+        let (cycle_a, id_a, cycle_b, id_b) = self.unpack_state(state, solution)?;
+        if solution.perms[cycle_a].len() < 2 || !instance.balance.allows_relocation(&solution.perms, cycle_a, cycle_b) {
+            return None
+        }
+        let (a_prev, a, a_next) = self.get_neighbors_in_cycle(id_a, &solution.perms[cycle_a]);
+        let (b_prev, b, _) = self.get_neighbors_in_cycle(id_b, &solution.perms[cycle_b]);
+        if solution.cycle[a] == SHARED {
+            return None
+        }
+        let delta = instance.dist_k(b_prev, a) + instance.dist_k(a, b) - instance.dist_k(b_prev, b)
+            + instance.dist_k(a_prev, a_next) - instance.dist_k(a_prev, a) - instance.dist_k(a, a_next)
+            + instance.relocation_overload_change(solution, a, cycle_b);
+        Some(delta)
+    }
+
+    fn apply(&self, state: usize, solution: &mut TSPSolution) {
+        let (cycle_a, id_a, cycle_b, id_b) = self.unpack_state(state, solution).unwrap();
+        let vertex = solution.perms[cycle_a].remove(id_a);
+        // inserting in front of the first vertex closes the cycle the same way as appending,
+        // which keeps a shared depot in place
+        let id_b = if id_b == 0 { solution.perms[cycle_b].len() } else { id_b };
+        solution.perms[cycle_b].insert(id_b, vertex);
+        solution.cycle[vertex] = cycle_b;
+        for (cycle, from) in [(cycle_a, id_a), (cycle_b, id_b)] {
+            for i in from..solution.perms[cycle].len() {
+                solution.order[solution.perms[cycle][i]] = i;
+            }
+        }
+    }
+
+    fn touched(&self, state: usize, _instance: &TSPInstance, solution: &TSPSolution) -> Vec<usize> {
+        let (cycle_a, id_a, cycle_b, id_b) = self.unpack_state(state, solution).unwrap();
+        let (a_prev, a, a_next) = self.get_neighbors_in_cycle(id_a, &solution.perms[cycle_a]);
+        let (b_prev, b, _) = self.get_neighbors_in_cycle(id_b, &solution.perms[cycle_b]);
+        vec![a_prev, a, a_next, b_prev, b]
+    }
+
+    fn show_state(&self, state: usize, solution: &TSPSolution) {
+        println!("{:?}", self.unpack_state(state, solution));
+    }
+
+    fn has_candidates(&self) -> bool {
+        true
+    }
+
+    // vertex is inserted right before or right after a neighbor in another cycle
+    fn candidates(&self, vertex: usize, neighbors: &[usize], solution: &TSPSolution) -> Vec<usize> {
+        let (cycle_a, id_a) = (solution.cycle[vertex], solution.order[vertex]);
+        let mut states = vec![];
+        for neighbor in neighbors.iter().filter(|neighbor| solution.is_exclusive(**neighbor) && solution.cycle[**neighbor] != cycle_a) {
+            let cycle_b = solution.cycle[*neighbor];
+            let n_b = solution.perms[cycle_b].len();
+            for id_b in [solution.order[*neighbor], (solution.order[*neighbor]+1)%n_b] {
+                states.push(self.pack_state(cycle_a, id_a, cycle_b, id_b, solution));
+            }
+        }
+        states
+    }
+}
@@ -0,0 +1,70 @@
+use crate::traits::Cost;
+use crate::tsp::neighborhoods::transition::Transition;
+use crate::tsp::def::{TSPInstance, TSPSolution, UNVISITED};
+
+pub struct RemoveTransition {}
+
+impl RemoveTransition {
+    pub fn new() -> RemoveTransition {
+        RemoveTransition {}
+    }
+
+    pub fn unpack_state(&self, state: usize, solution: &TSPSolution) -> Option<(usize, usize)> {
+        let mut position = state-1;
+        for (cycle, perm) in solution.perms.iter().enumerate() {
+            if perm.len() > position {
+                return Some((cycle, position))
+            }
+            position -= perm.len();
+        }
+        None
+    }
+}
+
+impl Default for RemoveTransition {
+    fn default() -> Self {
+        RemoveTransition::new()
+    }
+}
+
+impl Transition for RemoveTransition {
+
+    fn size(&self, solution: &TSPSolution) -> usize {
+        solution.perms.iter().map(|perm| perm.len()).sum()
+    }
+
+    fn score(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<Cost> {
+        let (cycle, position) = self.unpack_state(state, solution)?;
+        let (prev, vertex, next) = self.get_neighbors_in_cycle(position, &solution.perms[cycle]);
+        if solution.perms[cycle].len() < 2 || !solution.is_exclusive(vertex)
+            || !instance.balance.allows_changes(&solution.perms, &[(cycle, -1)])
+            || !instance.selection.reached(instance.dimension, instance.visited(solution) - 1,
+                                           instance.profit(solution) - instance.prize(vertex)) {
+            return None
+        }
+        let load = instance.load(&solution.perms[cycle]);
+        let delta = instance.dist_k(prev, next) - instance.dist_k(prev, vertex) - instance.dist_k(vertex, next)
+            + instance.prize(vertex) + instance.overload_change(&[(load, load - instance.demand(vertex))]);
+        Some(delta)
+    }
+
+    fn apply(&self, state: usize, solution: &mut TSPSolution) {
+        let (cycle, position) = self.unpack_state(state, solution).unwrap();
+        let vertex = solution.perms[cycle].remove(position);
+        solution.cycle[vertex] = UNVISITED;
+        solution.order[vertex] = 0;
+        for i in position..solution.perms[cycle].len() {
+            solution.order[solution.perms[cycle][i]] = i;
+        }
+    }
+
+    fn touched(&self, state: usize, _instance: &TSPInstance, solution: &TSPSolution) -> Vec<usize> {
+        let (cycle, position) = self.unpack_state(state, solution).unwrap();
+        let (prev, vertex, next) = self.get_neighbors_in_cycle(position, &solution.perms[cycle]);
+        vec![prev, vertex, next]
+    }
+
+    fn show_state(&self, state: usize, solution: &TSPSolution) {
+        println!("{:?}", self.unpack_state(state, solution));
+    }
+}
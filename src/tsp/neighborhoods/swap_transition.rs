@@ -0,0 +1,79 @@
+use crate::traits::Cost;
+use crate::tsp::neighborhoods::transition::Transition;
+use crate::tsp::def::{TSPInstance, TSPSolution, UNVISITED};
+
+// Replaces a visited vertex with an unvisited one, which changes the selection without changing the cycle sizes
+pub struct SwapTransition {}
+
+impl SwapTransition {
+    pub fn new() -> SwapTransition {
+        SwapTransition {}
+    }
+
+    // (unvisited vertex, cycle, position of the vertex it replaces)
+    pub fn unpack_state(&self, state: usize, solution: &TSPSolution) -> Option<(usize, usize, usize)> {
+        let total: usize = solution.perms.iter().map(|perm| perm.len()).sum();
+        if total == 0 {
+            return None
+        }
+        let state = state-1;
+        let vertex = state/total;
+        let mut position = state%total;
+        for (cycle, perm) in solution.perms.iter().enumerate() {
+            if perm.len() > position {
+                return Some((vertex, cycle, position))
+            }
+            position -= perm.len();
+        }
+        None
+    }
+}
+
+impl Default for SwapTransition {
+    fn default() -> Self {
+        SwapTransition::new()
+    }
+}
+
+impl Transition for SwapTransition {
+
+    fn size(&self, solution: &TSPSolution) -> usize {
+        solution.cycle.len()*solution.perms.iter().map(|perm| perm.len()).sum::<usize>()
+    }
+
+    fn score(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<Cost> {
+        let (vertex, cycle, position) = self.unpack_state(state, solution)?;
+        let (prev, replaced, next) = self.get_neighbors_in_cycle(position, &solution.perms[cycle]);
+        if solution.cycle[vertex] != UNVISITED || solution.perms[cycle].len() < 2 || !solution.is_exclusive(replaced)
+            || !instance.selection.reached(instance.dimension, instance.visited(solution),
+                                           instance.profit(solution) - instance.prize(replaced) + instance.prize(vertex)) {
+            return None
+        }
+        let load = instance.load(&solution.perms[cycle]);
+        let delta = instance.dist_k(prev, vertex) + instance.dist_k(vertex, next)
+            - instance.dist_k(prev, replaced) - instance.dist_k(replaced, next)
+            - instance.prize(vertex) + instance.prize(replaced)
+            + instance.overload_change(&[(load, load - instance.demand(replaced) + instance.demand(vertex))]);
+        Some(delta)
+    }
+
+    fn apply(&self, state: usize, solution: &mut TSPSolution) {
+        let (vertex, cycle, position) = self.unpack_state(state, solution).unwrap();
+        let replaced = solution.perms[cycle][position];
+        solution.perms[cycle][position] = vertex;
+        solution.cycle[vertex] = cycle;
+        solution.order[vertex] = position;
+        solution.cycle[replaced] = UNVISITED;
+        solution.order[replaced] = 0;
+    }
+
+    fn touched(&self, state: usize, _instance: &TSPInstance, solution: &TSPSolution) -> Vec<usize> {
+        let (vertex, cycle, position) = self.unpack_state(state, solution).unwrap();
+        let (prev, replaced, next) = self.get_neighbors_in_cycle(position, &solution.perms[cycle]);
+        vec![prev, replaced, vertex, next]
+    }
+
+    fn show_state(&self, state: usize, solution: &TSPSolution) {
+        println!("{:?}", self.unpack_state(state, solution));
+    }
+}
@@ -0,0 +1,198 @@
+use std::cell::OnceCell;
+
+use crate::traits::Cost;
+use crate::tsp::neighborhoods::transition::Transition;
+use crate::tsp::def::{TSPInstance, TSPSolution};
+
+const CASES: usize = 7;
+
+// Removes the edges leaving positions i < j < k of a cycle and reconnects the segments
+// s1 = perm[i+1..=j] and s2 = perm[j+1..=k] in one of the orders below, ' marks a reversed segment
+// 0: s1' s2, 1: s1 s2', 2: s2' s1', 3: s2 s1, 4: s2 s1', 5: s2' s1, 6: s1' s2'
+// The first three are 2-opt moves, the segment exchange 3 is the or-3opt move.
+// With a neighbor list only triples where perm[i] gets close to perm[j+1] and perm[i+1] to perm[k] are explored.
+pub struct ThreeOptTransition {
+    num_neighbors: Option<usize>,
+    neighbors: OnceCell<Vec<Vec<usize>>>,
+}
+
+fn choose_2(n: usize) -> usize {
+    n*n.saturating_sub(1)/2
+}
+
+fn choose_3(n: usize) -> usize {
+    n*n.saturating_sub(1)*n.saturating_sub(2)/6
+}
+
+// Inverse of the ranking of i < j < k by choose_3(k) + choose_2(j) + i
+fn unrank_triple(rank: usize) -> (usize, usize, usize) {
+    let mut k = ((6.0*rank as f64).cbrt() as usize).max(2);
+    while choose_3(k+1) <= rank {
+        k+=1;
+    }
+    while choose_3(k) > rank {
+        k-=1;
+    }
+    let rank = rank - choose_3(k);
+    let mut j = ((2.0*rank as f64).sqrt() as usize).max(1);
+    while choose_2(j+1) <= rank {
+        j+=1;
+    }
+    while choose_2(j) > rank {
+        j-=1;
+    }
+    (rank - choose_2(j), j, k)
+}
+
+impl ThreeOptTransition {
+    pub fn new() -> ThreeOptTransition {
+        ThreeOptTransition {
+            num_neighbors: None,
+            neighbors: OnceCell::new(),
+        }
+    }
+
+    pub fn with_neighbors(num_neighbors: usize) -> ThreeOptTransition {
+        ThreeOptTransition {
+            num_neighbors: Some(num_neighbors),
+            neighbors: OnceCell::new(),
+        }
+    }
+
+    fn cycle_size(&self, n: usize) -> usize {
+        if n < 3 {
+            return 0;
+        }
+        match self.num_neighbors {
+            Some(num_neighbors) => n*num_neighbors*num_neighbors*CASES,
+            None => choose_3(n)*CASES,
+        }
+    }
+
+    // (cycle, i, j, k, case)
+    pub fn unpack_state(&self, state: usize, solution: &TSPSolution) -> Option<(usize, usize, usize, usize, usize)> {
+        let mut state = state;
+        state-=1;
+        for (cycle, perm) in solution.perms.iter().enumerate() {
+            let size = self.cycle_size(perm.len());
+            if size > state {
+                let case = state%CASES;
+                let (i, j, k) = match self.num_neighbors {
+                    None => unrank_triple(state/CASES),
+                    Some(num_neighbors) => {
+                        // neighbor lists are built when the first move is scored, which always precedes applying it
+                        let neighbors = self.neighbors.get()?;
+                        let state = state/CASES;
+                        let (i, a, b) = (state/(num_neighbors*num_neighbors), state/num_neighbors%num_neighbors, state%num_neighbors);
+                        let n = perm.len();
+                        let (u, v) = (*neighbors[perm[i]].get(a)?, *neighbors[perm[(i+1)%n]].get(b)?);
+                        if solution.cycle[u] != cycle || solution.cycle[v] != cycle || solution.order[u] == 0 {
+                            return None
+                        }
+                        let (j, k) = (solution.order[u]-1, solution.order[v]);
+                        if !(i < j && j < k) {
+                            return None
+                        }
+                        (i, j, k)
+                    }
+                };
+                return Some((cycle, i, j, k, case));
+            }
+            state -= size;
+        }
+        None
+    }
+
+    // Change of the arcs inside perm[begin..=end] when it is walked backwards
+    fn reversal_delta(&self, begin: usize, end: usize, perm: &[usize], instance: &TSPInstance) -> Cost {
+        if instance.symmetric {
+            return 0;
+        }
+        (begin..end).map(|i| instance.dist_k(perm[i+1], perm[i]) - instance.dist_k(perm[i], perm[i+1])).sum()
+    }
+
+    // Segments in their new order as (begin, end, reversed)
+    fn arrangement(&self, i: usize, j: usize, k: usize, case: usize) -> [(usize, usize, bool); 2] {
+        let (s1, s2) = ((i+1, j), (j+1, k));
+        match case {
+            0 => [(s1.0, s1.1, true), (s2.0, s2.1, false)],
+            1 => [(s1.0, s1.1, false), (s2.0, s2.1, true)],
+            2 => [(s2.0, s2.1, true), (s1.0, s1.1, true)],
+            3 => [(s2.0, s2.1, false), (s1.0, s1.1, false)],
+            4 => [(s2.0, s2.1, false), (s1.0, s1.1, true)],
+            5 => [(s2.0, s2.1, true), (s1.0, s1.1, false)],
+            _ => [(s1.0, s1.1, true), (s2.0, s2.1, true)],
+        }
+    }
+}
+
+impl Default for ThreeOptTransition {
+    fn default() -> Self {
+        ThreeOptTransition::new()
+    }
+}
+
+impl Transition for ThreeOptTransition {
+
+    fn size(&self, solution: &TSPSolution) -> usize {
+        solution.perms.iter().map(|perm| self.cycle_size(perm.len())).sum()
+    }
+
+    fn score(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<Cost> {
+        if let Some(num_neighbors) = self.num_neighbors {
+            self.neighbors.get_or_init(|| instance.nearest_neighbors(num_neighbors));
+        }
+        let (cycle, i, j, k, case) = self.unpack_state(state, solution)?;
+        let perm = &solution.perms[cycle];
+        let n = perm.len();
+        let (before, after) = (perm[i], perm[(k+1)%n]);
+        let mut delta = -instance.dist_k(before, perm[i+1]) - instance.dist_k(perm[j], perm[j+1]) - instance.dist_k(perm[k], after);
+        let mut last = before;
+        for (begin, end, reversed) in self.arrangement(i, j, k, case) {
+            let (first, next_last) = if reversed { (perm[end], perm[begin]) } else { (perm[begin], perm[end]) };
+            delta += instance.dist_k(last, first);
+            if reversed {
+                delta += self.reversal_delta(begin, end, perm, instance);
+            }
+            last = next_last;
+        }
+        delta += instance.dist_k(last, after);
+        Some(delta)
+    }
+
+    fn apply(&self, state: usize, solution: &mut TSPSolution) {
+        let (cycle, i, j, k, case) = self.unpack_state(state, solution).unwrap();
+        let perm = &solution.perms[cycle];
+        let mut segment = Vec::with_capacity(k-i);
+        for (begin, end, reversed) in self.arrangement(i, j, k, case) {
+            if reversed {
+                segment.extend(perm[begin..=end].iter().rev());
+            } else {
+                segment.extend(perm[begin..=end].iter());
+            }
+        }
+        for (offset, vertex) in segment.into_iter().enumerate() {
+            solution.perms[cycle][i+1+offset] = vertex;
+            solution.order[vertex] = i+1+offset;
+        }
+    }
+
+    fn touched(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Vec<usize> {
+        let (cycle, i, j, k, case) = self.unpack_state(state, solution).unwrap();
+        let perm = &solution.perms[cycle];
+        let mut touched = vec![perm[i], perm[i+1], perm[j], perm[j+1], perm[k], perm[(k+1)%perm.len()]];
+        // arcs inside a reversed segment change direction as well
+        if !instance.symmetric {
+            for (begin, end, reversed) in self.arrangement(i, j, k, case) {
+                if reversed {
+                    touched.extend(perm[begin..=end].iter());
+                }
+            }
+        }
+        touched
+    }
+
+    fn show_state(&self, state: usize, solution: &TSPSolution) {
+        println!("{:?}", self.unpack_state(state, solution));
+    }
+}
@@ -1,13 +1,24 @@
+use crate::traits::Cost;
 use crate::tsp::def::TSPInstance;
 use crate::tsp::def::TSPSolution;
 
 pub trait Transition {
     fn size(&self, solution: &TSPSolution) -> usize;
-    fn score(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<f32>;
+    fn score(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<Cost>;
     fn apply(&self, state: usize, solution: &mut TSPSolution);
+    // Vertices whose neighbors in their cycle the move changes, read before it is applied
+    fn touched(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Vec<usize>;
     fn get_neighbors_in_cycle(&self, id: usize, perm:  &Vec<usize>) -> (usize, usize, usize) {
         let n = perm.len();
         (perm[(id+n-1)%n], perm[id], perm[(id+1)%n])
     }
     fn show_state(&self, state: usize, solution: &TSPSolution);
+    // Whether candidates lists the moves around a vertex, the others are searched by scanning all states
+    fn has_candidates(&self) -> bool {
+        false
+    }
+    // States of the moves that make vertex adjacent to one of the given vertices
+    fn candidates(&self, _vertex: usize, _neighbors: &[usize], _solution: &TSPSolution) -> Vec<usize> {
+        Vec::new()
+    }
 }
\ No newline at end of file
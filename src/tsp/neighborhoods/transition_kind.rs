@@ -0,0 +1,85 @@
+use json;
+
+use crate::tsp::neighborhoods::transition::Transition;
+use crate::tsp::neighborhoods::inter_cycle_transition::InterCycleTransition;
+use crate::tsp::neighborhoods::vertex_transition::VertexTransition;
+use crate::tsp::neighborhoods::edges_transition::EdgesTransition;
+use crate::tsp::neighborhoods::relocate_transition::RelocateTransition;
+use crate::tsp::neighborhoods::insert_transition::InsertTransition;
+use crate::tsp::neighborhoods::remove_transition::RemoveTransition;
+use crate::tsp::neighborhoods::swap_transition::SwapTransition;
+use crate::tsp::neighborhoods::or_opt_transition::OrOptTransition;
+use crate::tsp::neighborhoods::three_opt_transition::ThreeOptTransition;
+use crate::tsp::neighborhoods::cross_exchange_transition::CrossExchangeTransition;
+
+// A single transition named in the config, given either by its name or as {"type": name, ...parameters}
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransitionKind {
+    InterCycle,
+    Vertex,
+    Edges,
+    Relocate,
+    Insert,
+    Remove,
+    Swap,
+    // longest moved chain
+    OrOpt(usize),
+    // number of nearest neighbors the moves are restricted to, all moves if none
+    ThreeOpt(Option<usize>),
+    // longest exchanged segment
+    Cross(usize),
+}
+
+impl TransitionKind {
+    pub fn from_json(config: &json::JsonValue) -> TransitionKind {
+        let name = if config.is_string() { config.as_str() } else { config["type"].as_str() }
+            .unwrap_or_else(|| panic!("Missing transition type in {}", config));
+        match name {
+            "InterCycle" => TransitionKind::InterCycle,
+            "Vertex" => TransitionKind::Vertex,
+            "Edges" => TransitionKind::Edges,
+            "Relocate" => TransitionKind::Relocate,
+            "Insert" => TransitionKind::Insert,
+            "Remove" => TransitionKind::Remove,
+            "Swap" => TransitionKind::Swap,
+            "OrOpt" => TransitionKind::OrOpt(TransitionKind::max_segment(config).unwrap_or(3)),
+            "ThreeOpt" => TransitionKind::ThreeOpt(config["num_neighbors"].as_usize()),
+            "Cross" => TransitionKind::Cross(TransitionKind::max_segment(config).unwrap_or(3)),
+            _ => panic!("Unknown transition {}", name),
+        }
+    }
+
+    // Longest moved or exchanged segment, read from the same key by single transitions, presets and the memory solver
+    pub fn max_segment(config: &json::JsonValue) -> Option<usize> {
+        config["max_segment"].as_usize()
+    }
+
+    // Transitions searched together as one neighborhood structure, a single transition or a list of them
+    pub fn structure_from_json(config: &json::JsonValue) -> Vec<TransitionKind> {
+        if config.is_array() {
+            config.members().map(TransitionKind::from_json).collect()
+        } else {
+            vec![TransitionKind::from_json(config)]
+        }
+    }
+
+    pub fn create(&self) -> Box<dyn Transition> {
+        match self {
+            TransitionKind::InterCycle => Box::new(InterCycleTransition::new()),
+            TransitionKind::Vertex => Box::new(VertexTransition::new()),
+            TransitionKind::Edges => Box::new(EdgesTransition::new()),
+            TransitionKind::Relocate => Box::new(RelocateTransition::new()),
+            TransitionKind::Insert => Box::new(InsertTransition::new()),
+            TransitionKind::Remove => Box::new(RemoveTransition::new()),
+            TransitionKind::Swap => Box::new(SwapTransition::new()),
+            TransitionKind::OrOpt(max_length) => Box::new(OrOptTransition::with_max_length(*max_length)),
+            TransitionKind::ThreeOpt(Some(num_neighbors)) => Box::new(ThreeOptTransition::with_neighbors(*num_neighbors)),
+            TransitionKind::ThreeOpt(None) => Box::new(ThreeOptTransition::new()),
+            TransitionKind::Cross(max_length) => Box::new(CrossExchangeTransition::with_max_length(*max_length)),
+        }
+    }
+
+    pub fn create_all(kinds: &[TransitionKind]) -> Vec<Box<dyn Transition>> {
+        kinds.iter().map(TransitionKind::create).collect()
+    }
+}
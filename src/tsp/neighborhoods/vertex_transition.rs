@@ -1,5 +1,6 @@
+use crate::traits::Cost;
 use crate::tsp::neighborhoods::transition::Transition;
-use crate::tsp::def::TSPSolution;
+use crate::tsp::def::{TSPSolution, SHARED};
 use crate::tsp::def::TSPInstance;
 
 pub struct VertexTransition {}
@@ -11,60 +12,66 @@ impl VertexTransition {
     fn unpack_state(&self, state: usize, solution: &TSPSolution) -> Option<(usize, usize, usize)> {
         let mut state = state;
         state-=1;
-        let (n_a, n_b) = (solution.perm_a.len(), solution.perm_b.len());
-        if n_a*(n_a-1) > state { //first cycle
-            let a = state/(n_a-1);
-            let mut b = state%(n_a-1);
-            if b >= a {
-                b+=1;
-            } 
-            Some((0, a, b))
-        } else if n_a*(n_a-1) + n_b*(n_b-1) > state {
-            state -= n_a*(n_a-1);
-            let a = state/(n_b-1);
-            let mut b = state%(n_b-1);
-            if b >= a {
-                b+=1;
+        for (cycle, perm) in solution.perms.iter().enumerate() {
+            let n = perm.len();
+            let size = n*n.saturating_sub(1);
+            if size > state {
+                let a = state/(n-1);
+                let mut b = state%(n-1);
+                if b >= a {
+                    b+=1;
+                }
+                return Some((cycle, a, b))
             }
-            Some((1, a, b))
-        } else {
-            return None
+            state -= size;
         }
+        None
     }
 }
 
 impl Transition for VertexTransition {
 
     fn size(&self, solution: &TSPSolution) -> usize {
-        let (n_a, n_b) = (solution.perm_a.len(), solution.perm_b.len());
-        n_a*(n_a-1) + (n_b)*(n_b-1)
+        solution.perms.iter().map(|perm| perm.len()*perm.len().saturating_sub(1)).sum()
     }
     
-    fn score(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<f32> {
+    fn score(&self, state: usize, instance: &TSPInstance, solution: &TSPSolution) -> Option<Cost> {
         let st = self.unpack_state(state, solution);
         if st.is_none() {
             return None;
         }
         let (cycle, vertex_a, vertex_b) = st.unwrap();
-        let perm = vec![&solution.perm_a, &solution.perm_b];
-        let (a_prev, a, a_next) = self.get_neighbors_in_cycle(vertex_a, perm[cycle]);
-        let (b_prev, b, b_next) = self.get_neighbors_in_cycle(vertex_b, perm[cycle]);
+        let perm = &solution.perms;
+        let (a_prev, a, a_next) = self.get_neighbors_in_cycle(vertex_a, &perm[cycle]);
+        let (b_prev, b, b_next) = self.get_neighbors_in_cycle(vertex_b, &perm[cycle]);
+        if solution.cycle[a] == SHARED || solution.cycle[b] == SHARED {
+            return None;
+        }
         let mut delta = instance.dist_k(b_prev, a) + instance.dist_k(a, b_next)
             + instance.dist_k(a_prev, b) + instance.dist_k(b, a_next)
             - instance.dist_k(a_prev, a) - instance.dist_k(a, a_next)
             - instance.dist_k(b_prev, b) - instance.dist_k(b, b_next);
         if (vertex_a+1)%perm[cycle].len() == vertex_b || (vertex_b+1)%perm[cycle].len() == vertex_a {
-            delta+= 2.0*instance.dist_k(a, b);
+            delta+= instance.dist_k(a, b) + instance.dist_k(b, a);
         }
         Some(delta)
     }
 
     fn apply(&self, state: usize, solution: &mut TSPSolution) {
         let (cycle, vertex_a, vertex_b) = self.unpack_state(state, solution).unwrap();
-        let perm = if cycle == 0 { &mut solution.perm_a } else { &mut solution.perm_b };
+        let perm = &mut solution.perms[cycle];
         let t = perm[vertex_a];
         perm[vertex_a] = perm[vertex_b];
         perm[vertex_b] = t;
+        solution.order[perm[vertex_a]] = vertex_a;
+        solution.order[perm[vertex_b]] = vertex_b;
+    }
+
+    fn touched(&self, state: usize, _instance: &TSPInstance, solution: &TSPSolution) -> Vec<usize> {
+        let (cycle, vertex_a, vertex_b) = self.unpack_state(state, solution).unwrap();
+        let (a_prev, a, a_next) = self.get_neighbors_in_cycle(vertex_a, &solution.perms[cycle]);
+        let (b_prev, b, b_next) = self.get_neighbors_in_cycle(vertex_b, &solution.perms[cycle]);
+        vec![a_prev, a, a_next, b_prev, b, b_next]
     }
 
     fn show_state(&self, state: usize, solution: &TSPSolution) {
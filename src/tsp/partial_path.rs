@@ -1,3 +1,4 @@
+use crate::traits::Cost;
 use crate::tsp::def::TSPInstance;
 
 pub struct PartialPath<'a> {
@@ -6,11 +7,26 @@ pub struct PartialPath<'a> {
 }
 
 impl PartialPath<'_> {
-    pub fn try_insert(&self, pos: usize, id: usize) -> f32 {
+    pub fn try_insert(&self, pos: usize, id: usize) -> Cost {
         let n = self.vec.len() as i32;
         let prev = ((((pos as i32) - 1) % n + n) % n) as usize;
         let next = pos;
+        let prize = if self.instance.selection.is_selective() { self.instance.prize(id) } else { 0 };
         - self.instance.dist_k(self.vec[prev], self.vec[next])
-            + self.instance.dist_k(self.vec[prev], id) + self.instance.dist_k(id, self.vec[next])
+            + self.instance.dist_k(self.vec[prev], id) + self.instance.dist_k(id, self.vec[next]) - prize
     }
-}
\ No newline at end of file
+
+    // Unvisited vertices that still fit into the path, if none does the ones overloading it the least,
+    // which eval penalises so that local search moves them out again
+    pub fn candidates(&self, visited: &[bool]) -> Vec<bool> {
+        let load = self.instance.load(&self.vec);
+        let overload = |v: usize| self.instance.overload(load + self.instance.demand(v));
+        let least = (0..visited.len()).filter(|v| !visited[*v]).map(overload).min();
+        (0..visited.len()).map(|v| !visited[v] && Some(overload(v)) == least).collect()
+    }
+
+    pub fn has_room(&self, visited: &[bool]) -> bool {
+        let load = self.instance.load(&self.vec);
+        (0..visited.len()).any(|v| !visited[v] && self.instance.fits(load + self.instance.demand(v)))
+    }
+}
@@ -1,6 +1,6 @@
 use crate::tsp::partial_path::PartialPath;
 
-pub trait Picker {
-    fn add_both(&self, partial_path_a: &mut PartialPath, partial_path_b: &mut PartialPath, visited: &mut Vec<bool>);
-    fn add(&self, partial_path_a: &mut PartialPath, partial_path_b: &mut PartialPath, visited: &mut Vec<bool>);
-}
\ No newline at end of file
+pub trait Picker: Send + Sync {
+    fn add_all(&self, partial_paths: &mut [PartialPath], visited: &mut [bool]);
+    fn add(&self, index: usize, partial_paths: &mut [PartialPath], visited: &mut [bool]);
+}
@@ -1,16 +1,18 @@
+use crate::traits::Cost;
 use crate::tsp::picker::Picker;
 use crate::tsp::partial_path::PartialPath;
 
 pub struct CyclePicker;
 
 impl Picker for CyclePicker {
-    fn add_both(&self, partial_path_a: &mut PartialPath, partial_path_b: &mut PartialPath, visited: &mut Vec<bool>) {
-        self._add(partial_path_a, visited);
-        self._add(partial_path_b, visited);
+    fn add_all(&self, partial_paths: &mut [PartialPath], visited: &mut [bool]) {
+        for partial_path in partial_paths.iter_mut() {
+            self._add(partial_path, visited);
+        }
     }
 
-    fn add(&self, partial_path_a: &mut PartialPath, partial_path_b: &mut PartialPath, visited: &mut Vec<bool>) {
-        unimplemented!()
+    fn add(&self, index: usize, partial_paths: &mut [PartialPath], visited: &mut [bool]) {
+        self._add(&mut partial_paths[index], visited);
     }
 }
 
@@ -18,12 +20,12 @@ impl CyclePicker {
     pub fn new() -> CyclePicker{
         CyclePicker
     }
-    pub fn _add(&self, partial_path: &mut PartialPath, visited: &mut Vec<bool>) {
-        let n = partial_path.instance.dimension;
-        let mut min = (f32::MAX, 0, 0);
+    pub fn _add(&self, partial_path: &mut PartialPath, visited: &mut [bool]) {
+        let candidates = partial_path.candidates(visited);
+        let mut min = (Cost::MAX, 0, 0);
         for i in 0..partial_path.vec.len() {
-            for j in 0..n {
-                if !visited[j] {
+            for (j, candidate) in candidates.iter().enumerate() {
+                if *candidate {
                     let new_score = partial_path.try_insert(i, j);
                     if min.0 > new_score {
                         min = (new_score, i, j);
@@ -34,4 +36,4 @@ impl CyclePicker {
         visited[min.2] = true;
         partial_path.vec.insert(min.1, min.2);
     }
-}
\ No newline at end of file
+}
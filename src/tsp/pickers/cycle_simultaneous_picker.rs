@@ -1,44 +1,51 @@
+use crate::traits::Cost;
 use crate::tsp::picker::Picker;
 use crate::tsp::partial_path::PartialPath;
 
 pub struct CycleSimultaneousPicker;
 
 impl Picker for CycleSimultaneousPicker {
-    fn add_both(&self, partial_path_a: &mut PartialPath, partial_path_b: &mut PartialPath, visited: &mut Vec<bool>) {
-        let cost_a = self.get_costs(partial_path_a, visited);
-        let cost_b = self.get_costs(partial_path_b, visited);
-        let n = visited.len();
-        let mut min_pair = (f32::MAX, 0, 0);
-        for i in 0..n {
-            if !visited[i] {
-                for j in 0..n {
-                    if visited[j] || i == j {
-                        continue;
-                    }
-                    if cost_a[i].0 + cost_b[j].0 < min_pair.0 {
-                        min_pair = (cost_a[i].0 + cost_b[j].0, i, j);
-                    }
-                }
-            }
+    fn add_all(&self, partial_paths: &mut [PartialPath], visited: &mut [bool]) {
+        let k = partial_paths.len();
+        let costs: Vec<Vec<(Cost, usize)>> = partial_paths.iter()
+            .map(|partial_path| self.get_costs(partial_path, visited))
+            .collect();
+        // an optimal assignment only ever uses the k cheapest vertices of every path
+        let candidates: Vec<Vec<usize>> = costs.iter().map(|cost| {
+            let mut vertices: Vec<usize> = (0..visited.len()).filter(|v| cost[*v].0 < Cost::MAX).collect();
+            vertices.sort_by(|a, b| cost[*a].0.partial_cmp(&cost[*b].0).unwrap().then(a.cmp(b)));
+            vertices.truncate(k);
+            vertices
+        }).collect();
+        let mut best = (Cost::MAX, Vec::new());
+        self.assign(&costs, &candidates, 0, &mut Vec::with_capacity(k), &mut best);
+        for (index, vertex) in best.1.into_iter().enumerate() {
+            visited[vertex] = true;
+            partial_paths[index].vec.insert(costs[index][vertex].1, vertex);
         }
-        visited[min_pair.1] = true;
-        visited[min_pair.2] = true;
-        partial_path_a.vec.insert(cost_a[min_pair.1].1, min_pair.1);
-        partial_path_b.vec.insert(cost_b[min_pair.2].1, min_pair.2);
     }
 
-    fn add(&self, partial_path_a: &mut PartialPath, partial_path_b: &mut PartialPath, visited: &mut Vec<bool>) {
-        unimplemented!()
+    fn add(&self, index: usize, partial_paths: &mut [PartialPath], visited: &mut [bool]) {
+        let cost = self.get_costs(&partial_paths[index], visited);
+        let candidates = partial_paths[index].candidates(visited);
+        let mut min = (Cost::MAX, 0);
+        for j in 0..visited.len() {
+            if candidates[j] && cost[j].0 < min.0 {
+                min = (cost[j].0, j);
+            }
+        }
+        visited[min.1] = true;
+        partial_paths[index].vec.insert(cost[min.1].1, min.1);
     }
 }
 
 impl CycleSimultaneousPicker {
-    pub fn get_costs(&self, partial_path: &mut PartialPath, visited: &mut Vec<bool>) -> Vec<(f32, usize)> {
-        let n = partial_path.instance.dimension;
-        let mut min_increase: Vec<(f32, usize)> = Vec::new();
-        for j in 0..n {
-            if !visited[j] {
-                let mut min = (f32::MAX, 0);
+    pub fn get_costs(&self, partial_path: &PartialPath, visited: &[bool]) -> Vec<(Cost, usize)> {
+        let candidates = partial_path.candidates(visited);
+        let mut min_increase: Vec<(Cost, usize)> = Vec::new();
+        for (j, candidate) in candidates.iter().enumerate() {
+            if *candidate {
+                let mut min = (Cost::MAX, 0);
                 for i in 0..partial_path.vec.len() {
                     let new_score = partial_path.try_insert(i, j);
                     if new_score < min.0 {
@@ -47,9 +54,34 @@ impl CycleSimultaneousPicker {
                 }
                 min_increase.push(min);
             } else {
-                min_increase.push((f32::MAX, 0));
+                min_increase.push((Cost::MAX, 0));
             }
         }
         min_increase
     }
-}
\ No newline at end of file
+
+    // Picks a distinct vertex for every path minimizing the total cost,
+    // ties are resolved in favour of the lexicographically smallest choice
+    fn assign(&self, costs: &[Vec<(Cost, usize)>], candidates: &[Vec<usize>], total: Cost,
+              current: &mut Vec<usize>, best: &mut (Cost, Vec<usize>)) {
+        let index = current.len();
+        if index == costs.len() {
+            if total < best.0 || (total == best.0 && *current < best.1) {
+                *best = (total, current.clone());
+            }
+            return;
+        }
+        let bound = candidates[index..].iter().zip(costs[index..].iter())
+            .fold(total, |acc, (candidate, cost)| acc + cost[candidate[0]].0);
+        if bound > best.0 {
+            return;
+        }
+        for vertex in candidates[index].iter() {
+            if !current.contains(vertex) {
+                current.push(*vertex);
+                self.assign(costs, candidates, total + costs[index][*vertex].0, current, best);
+                current.pop();
+            }
+        }
+    }
+}
@@ -1,30 +1,32 @@
+use crate::traits::Cost;
 use crate::tsp::picker::Picker;
 use crate::tsp::partial_path::PartialPath;
 
 pub struct NearestPicker;
 
 impl Picker for NearestPicker {
-    fn add_both(&self, partial_path_a: &mut PartialPath, partial_path_b: &mut PartialPath, visited: &mut Vec<bool>) {
-        self._add(partial_path_a, visited);
-        self._add(partial_path_b, visited);
+    fn add_all(&self, partial_paths: &mut [PartialPath], visited: &mut [bool]) {
+        for partial_path in partial_paths.iter_mut() {
+            self._add(partial_path, visited);
+        }
     }
 
-    fn add(&self, partial_path_a: &mut PartialPath, partial_path_b: &mut PartialPath, visited: &mut Vec<bool>) {
-        unimplemented!()
+    fn add(&self, index: usize, partial_paths: &mut [PartialPath], visited: &mut [bool]) {
+        self._add(&mut partial_paths[index], visited);
     }
 }
 
 impl NearestPicker {
-    fn _add(&self, partial_path: &mut PartialPath<'_>, visited: &mut std::vec::Vec<bool>) {
-        let mut min = (f32::MAX, 0);
-        let n = partial_path.instance.dimension;
-        for i in 0..n {
+    fn _add(&self, partial_path: &mut PartialPath<'_>, visited: &mut [bool]) {
+        let candidates = partial_path.candidates(visited);
+        let mut min = (Cost::MAX, 0);
+        for (i, candidate) in candidates.iter().enumerate() {
             let distance = partial_path.instance.dist_k(*partial_path.vec.last().unwrap(), i);
-            if !visited[i] && distance < min.0 {
+            if *candidate && distance < min.0 {
                 min = (distance, i);
             }
         }
         visited[min.1] = true;
         partial_path.vec.push(min.1);
     }
-}
\ No newline at end of file
+}
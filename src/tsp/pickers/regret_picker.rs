@@ -1,3 +1,4 @@
+use crate::traits::Cost;
 use crate::tsp::picker::Picker;
 use crate::tsp::partial_path::PartialPath;
 use crate::tsp::pickers::cycle_picker::CyclePicker;
@@ -5,22 +6,25 @@ use crate::tsp::pickers::cycle_picker::CyclePicker;
 pub struct RegretPicker;
 
 impl Picker for RegretPicker {
-    fn add_both(&self, partial_path_a: &mut PartialPath, partial_path_b: &mut PartialPath, visited: &mut Vec<bool>) {
-        self.add(partial_path_a, partial_path_b, visited);
-        self.add(partial_path_b, partial_path_a, visited);
+    fn add_all(&self, partial_paths: &mut [PartialPath], visited: &mut [bool]) {
+        for index in 0..partial_paths.len() {
+            self.add(index, partial_paths, visited);
+        }
     }
-    fn add(&self, partial_path: &mut PartialPath, other_partial_path: &mut PartialPath, visited: &mut Vec<bool>) {
-        let n = partial_path.instance.dimension;
+    fn add(&self, index: usize, partial_paths: &mut [PartialPath], visited: &mut [bool]) {
+        let partial_path = &partial_paths[index];
         if partial_path.vec.len() < 3 {
             let picker = CyclePicker::new();
-            picker._add(partial_path, visited);
+            picker._add(&mut partial_paths[index], visited);
             return;
         }
-        let mut max_regret = (f32::MIN, 0, 0);
-        for j in 0..n {
-            if !visited[j] {
-                let mut min1 = (f32::MAX, 0);
-                let mut min2 = f32::MAX;
+        let candidates = partial_path.candidates(visited);
+        let loads: Vec<usize> = partial_paths.iter().map(|path| path.instance.load(&path.vec)).collect();
+        let mut max_regret = (Cost::MIN, 0, 0);
+        for (j, candidate) in candidates.iter().enumerate() {
+            if *candidate {
+                let mut min1 = (Cost::MAX, 0);
+                let mut min2 = Cost::MAX;
                 for i in 0..partial_path.vec.len() {
                     let new_score = partial_path.try_insert(i, j);
                     if new_score < min1.0 {
@@ -30,10 +34,15 @@ impl Picker for RegretPicker {
                         min2 = new_score;
                     }
                 }
-                for i in 0..other_partial_path.vec.len() { //check if it's better to put this vertex on the second path
-                    let new_score = other_partial_path.try_insert(i, j);
-                    if new_score < min2 {
-                        min2 = new_score;
+                for (other_index, other_partial_path) in partial_paths.iter().enumerate() {
+                    if other_index == index || !partial_path.instance.fits(loads[other_index] + partial_path.instance.demand(j)) {
+                        continue;
+                    }
+                    for i in 0..other_partial_path.vec.len() { //check if it's better to put this vertex on another path
+                        let new_score = other_partial_path.try_insert(i, j);
+                        if new_score < min2 {
+                            min2 = new_score;
+                        }
                     }
                 }
                 if min2 - min1.0 > max_regret.0 {
@@ -42,6 +51,6 @@ impl Picker for RegretPicker {
             }
         }
         visited[max_regret.2] = true;
-        partial_path.vec.insert(max_regret.1, max_regret.2);
+        partial_paths[index].vec.insert(max_regret.1, max_regret.2);
     }
-}
\ No newline at end of file
+}
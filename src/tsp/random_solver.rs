@@ -1,7 +1,10 @@
 use crate::tsp::def::TSPSolution;
 use crate::tsp::def::TSPInstance;
+use rand::rngs::StdRng;
 use crate::traits::Solver;
 use crate::utils::random_permutation;
+use std::cmp::Reverse;
+use crate::tsp::selection::Selection;
 
 pub struct RandomSolver;
 
@@ -9,18 +12,76 @@ impl RandomSolver {
     pub fn new() -> RandomSolver {
         RandomSolver
     }
+
+    // vertices are assigned in order of decreasing demand, each to the cycle furthest below its target
+    // that still has capacity for it, or to the one it overloads the least, and keep their random order within the cycle
+    fn first_fit(perm: &[usize], targets: &[usize], instance: &TSPInstance) -> Vec<Vec<usize>> {
+        let k = targets.len();
+        let mut by_demand = perm.to_vec();
+        by_demand.sort_by_key(|vertex| Reverse(instance.demand(*vertex)));
+        let mut assigned = vec![0; instance.dimension];
+        let mut sizes = vec![0; k];
+        let mut loads = vec![instance.depot.map_or(0, |depot| instance.demand(depot)); k];
+        for vertex in by_demand.iter() {
+            let demand = instance.demand(*vertex);
+            let key = |c: usize| (Reverse(instance.overload(loads[c] + demand)), targets[c] as isize - sizes[c] as isize);
+            let mut best = 0;
+            for c in 1..k {
+                if key(c) > key(best) {
+                    best = c;
+                }
+            }
+            assigned[*vertex] = best;
+            sizes[best] += 1;
+            loads[best] += demand;
+        }
+        let mut perms: Vec<Vec<usize>> = vec![Vec::new(); k];
+        for vertex in perm.iter() {
+            perms[assigned[*vertex]].push(*vertex);
+        }
+        perms
+    }
 }
 
 impl Solver<TSPInstance, TSPSolution> for RandomSolver {
-    fn solve(&self, start_vertex: usize, instance: &TSPInstance) -> TSPSolution {
-        let perm = random_permutation(instance.dimension);
-        TSPSolution::new(
-            perm[..(perm.len() + 1)/2].to_vec(),
-            perm[(perm.len() + 1)/2..].to_vec()
-        )
+    fn solve(&self, _start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng) -> TSPSolution {
+        let mut perm: Vec<usize> = random_permutation(instance.dimension, rng).into_iter()
+            .filter(|vertex| Some(*vertex) != instance.depot)
+            .collect();
+        if let Selection::Profit(threshold) = instance.selection {
+            let mut profit = instance.depot.map_or(0, |depot| instance.prize(depot));
+            let size = perm.iter().take_while(|vertex| {
+                let reached = profit >= threshold;
+                profit += instance.prize(**vertex);
+                !reached
+            }).count();
+            perm.truncate(size);
+        } else {
+            perm.truncate(instance.selection.size(instance.dimension).saturating_sub(instance.depot.map_or(0, |_| 1)));
+        }
+        let k = instance.no_cycles;
+        let targets = instance.balance.targets(perm.len(), k);
+        let mut perms = if instance.capacity.is_some() {
+            RandomSolver::first_fit(&perm, &targets, instance)
+        } else {
+            let mut perms = Vec::with_capacity(k);
+            let mut begin = 0;
+            for target in targets.iter() {
+                let end = begin + target;
+                perms.push(perm[begin..end].to_vec());
+                begin = end;
+            }
+            perms
+        };
+        if let Some(depot) = instance.depot {
+            for perm in perms.iter_mut() {
+                perm.insert(0, depot);
+            }
+        }
+        TSPSolution::with_dimension(perms, instance.dimension)
     }
 
-    fn solve_s(&self, start_vertex: usize, instance: &TSPInstance, solution: TSPSolution) -> TSPSolution {
+    fn solve_s(&self, _start_vertex: usize, _instance: &TSPInstance, _solution: TSPSolution, _rng: &mut StdRng) -> TSPSolution {
         unimplemented!()
     }
-}
\ No newline at end of file
+}
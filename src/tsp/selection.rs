@@ -0,0 +1,45 @@
+use json;
+use crate::traits::Cost;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Selection {
+    #[default]
+    All,
+    Count(usize),
+    Ratio(f32),
+    Profit(Cost),
+}
+
+impl Selection {
+    pub fn from_json(config: &json::JsonValue) -> Selection {
+        if config["type"] == "Count" {
+            Selection::Count(config["count"].as_usize().unwrap())
+        } else if config["type"] == "Ratio" {
+            Selection::Ratio(config["ratio"].as_f32().unwrap())
+        } else if config["type"] == "Profit" {
+            Selection::Profit(config["profit"].as_i64().unwrap())
+        } else {
+            Selection::All
+        }
+    }
+
+    pub fn is_selective(&self) -> bool {
+        *self != Selection::All
+    }
+
+    // Number of vertices a solution is built with, profit based selections may stop earlier
+    pub fn size(&self, n: usize) -> usize {
+        match self {
+            Selection::Count(count) => (*count).min(n),
+            Selection::Ratio(ratio) => ((*ratio * n as f32).round() as usize).min(n),
+            _ => n,
+        }
+    }
+
+    pub fn reached(&self, n: usize, visited: usize, profit: Cost) -> bool {
+        match self {
+            Selection::Profit(threshold) => profit >= *threshold,
+            _ => visited >= self.size(n),
+        }
+    }
+}
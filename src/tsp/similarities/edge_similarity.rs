@@ -1,7 +1,6 @@
 use crate::tsp::similarity::Similarity;
-use crate::tsp::def::{TSPSolution, TSPInstance};
-use std::cmp::max;
-use crate::tsp::similarities::vertex_similarity::VertexSimilarity;
+use crate::tsp::def::{TSPSolution, TSPInstance, SHARED};
+use crate::utils::best_assignment;
 
 pub struct EdgeSimilarity;
 
@@ -10,40 +9,26 @@ impl EdgeSimilarity {
         EdgeSimilarity {}
     }
 
-    fn _sim(&self, solution_a: &TSPSolution, solution_b: &TSPSolution, reverse: bool) -> usize {
-        let mut a_cycle_dest = 0;
-        let mut a_perm_a = &solution_a.perm_a;
-        let mut a_perm_b = &solution_a.perm_b;
-
-        let b_perm_a = &solution_b.perm_a;
-        let b_perm_b = &solution_b.perm_b;
+    // Number of edges of every cycle of solution_a that are also present in every cycle of solution_b
+    fn _sim(&self, solution_a: &TSPSolution, solution_b: &TSPSolution) -> Vec<Vec<usize>> {
+        let k = solution_a.perms.len();
         let b_order = &solution_b.order;
         let b_cycle = &solution_b.cycle;
-
-        if reverse {
-            a_perm_a = &solution_a.perm_b;
-            a_perm_b = &solution_a.perm_a;
-            a_cycle_dest = 1;
-        }
-
-        let mut similarity: usize = 0;
-        let n = a_perm_a.len();
-
-        for a_vert_i in 0..n {
-            let g_vert = a_perm_a[a_vert_i];
-            if b_cycle[g_vert] == a_cycle_dest
-                && (b_perm_a[(b_order[g_vert] + 1) % n] == a_perm_a[(a_vert_i + 1) % n]
-                || b_perm_a[(b_order[g_vert] + n - 1) % n] == a_perm_a[(a_vert_i + 1) % n]) {
-                similarity += 1;
-            }
-        }
-
-        for a_vert_i in 0..n {
-            let g_vert = a_perm_b[a_vert_i];
-            if b_cycle[g_vert] == 1 - a_cycle_dest
-                && (b_perm_b[(b_order[g_vert] + 1) % n] == a_perm_b[(a_vert_i + 1) % n]
-                || b_perm_b[(b_order[g_vert] + n - 1) % n] == a_perm_b[(a_vert_i + 1) % n]) {
-                similarity += 1;
+        let mut similarity = vec![vec![0; k]; k];
+
+        for (a_cycle, a_perm) in solution_a.perms.iter().enumerate() {
+            let n = a_perm.len();
+            for a_vert_i in 0..n {
+                let (g_vert, g_next) = (a_perm[a_vert_i], a_perm[(a_vert_i + 1) % n]);
+                let (g_vert, g_next) = if b_cycle[g_vert] == SHARED { (g_next, g_vert) } else { (g_vert, g_next) };
+                if !solution_b.is_exclusive(g_vert) {
+                    continue;
+                }
+                let b_perm = &solution_b.perms[b_cycle[g_vert]];
+                let m = b_perm.len();
+                if b_perm[(b_order[g_vert] + 1) % m] == g_next || b_perm[(b_order[g_vert] + m - 1) % m] == g_next {
+                    similarity[a_cycle][b_cycle[g_vert]] += 1;
+                }
             }
         }
 
@@ -53,6 +38,6 @@ impl EdgeSimilarity {
 
 impl Similarity for EdgeSimilarity {
     fn sim(&self, instance: &TSPInstance, solution_a: &TSPSolution, solution_b: &TSPSolution) -> usize {
-        return max(self._sim(solution_a, solution_b, true), self._sim(solution_a, solution_b, false));
+        best_assignment(&self._sim(solution_a, solution_b)).0
     }
-}
\ No newline at end of file
+}
@@ -11,18 +11,15 @@ impl VertexSimilarity {
 
 impl Similarity for VertexSimilarity {
     fn sim(&self, instance: &TSPInstance, solution_a: &TSPSolution, solution_b: &TSPSolution) -> usize {
+        let matching = solution_a.matching(solution_b);
         let mut similarity = 0;
 
         for vertex_i in 0..instance.dimension {
-            if solution_a.cycle[vertex_i] == solution_b.cycle[vertex_i] {
+            if solution_a.is_exclusive(vertex_i) && matching[solution_a.cycle[vertex_i]] == solution_b.cycle[vertex_i] {
                 similarity += 1;
             }
         }
 
-        if similarity < instance.dimension / 2 {
-            similarity = instance.dimension - similarity;
-        }
-
         return similarity;
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,5 @@
 use crate::tsp::def::{TSPSolution, TSPInstance};
 
-pub trait Similarity {
+pub trait Similarity: Send + Sync {
     fn sim(&self, instance: &TSPInstance, solution_a: &TSPSolution, solution_b: &TSPSolution) -> usize;
 }
\ No newline at end of file
@@ -0,0 +1,115 @@
+use rand::Rng;
+use rand::rngs::StdRng;
+
+use crate::traits::{Instance, Solver, Cost, Observer, NoObserver};
+use crate::tsp::def::{TSPInstance, TSPSolution};
+use crate::tsp::neighborhoods::neighborhood::Neighborhood;
+use crate::tsp::neighborhoods::transition_kind::TransitionKind;
+use crate::tsp::termination::{Termination, Progress};
+use crate::tsp::cooling::Cooling;
+
+// Samples random moves of the transitions, improving ones are always applied and worsening ones
+// with probability exp(-delta/temperature). The temperature changes every epoch moves.
+pub struct SimulatedAnnealingSolver {
+    initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
+    termination: Termination,
+    cooling: Cooling,
+    epoch: usize,
+    // share of the sampled worsening moves accepted at the initial temperature
+    initial_acceptance: f64,
+    samples: usize,
+    transition: Vec<TransitionKind>,
+}
+
+impl SimulatedAnnealingSolver {
+    pub fn new(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
+               termination: Termination,
+               cooling: Cooling,
+               epoch: usize,
+               initial_acceptance: f64,
+               samples: usize,
+               transition: Vec<TransitionKind>) -> SimulatedAnnealingSolver {
+        SimulatedAnnealingSolver {
+            initial_solver,
+            termination,
+            cooling,
+            epoch,
+            initial_acceptance,
+            samples,
+            transition,
+        }
+    }
+
+    // Temperature at which a worsening move of the average sampled size is accepted with the initial acceptance
+    fn initial_temperature(&self, instance: &TSPInstance, solution: &TSPSolution, neighborhood: &mut Neighborhood, rng: &mut StdRng) -> f64 {
+        let deltas: Vec<Cost> = (0..self.samples)
+            .filter_map(|_| {
+                let state = neighborhood.next_random(rng);
+                neighborhood.score(state, instance, solution)
+            })
+            .filter(|delta| *delta > 0)
+            .collect();
+        if deltas.is_empty() {
+            return 1.0;
+        }
+        let average = deltas.iter().sum::<Cost>() as f64 / deltas.len() as f64;
+        -average / self.initial_acceptance.ln()
+    }
+
+    fn accepts(&self, delta: Cost, temperature: f64, rng: &mut StdRng) -> bool {
+        delta <= 0 || (temperature > 0.0 && rng.gen::<f64>() < (-(delta as f64) / temperature).exp())
+    }
+
+    fn anneal(&self, instance: &TSPInstance, mut solution: TSPSolution, rng: &mut StdRng, observer: &mut dyn Observer) -> TSPSolution {
+        let mut progress = Progress::observed(observer);
+        let mut current = instance.eval(&solution);
+        progress.evaluation(current);
+        let mut best_solution: (Cost, TSPSolution) = (current, solution.deep_clone());
+        let mut neighborhood = Neighborhood::new(TransitionKind::create_all(&self.transition), &solution, true, rng);
+        let initial = self.initial_temperature(instance, &solution, &mut neighborhood, rng);
+        let mut temperature = initial;
+        let mut epoch_best = current;
+        let mut stagnation = 0;
+        while !self.termination.reached(&progress) {
+            let state = neighborhood.next_random(rng);
+            if let Some(delta) = neighborhood.score(state, instance, &solution) {
+                progress.evaluation(current + delta);
+                if self.accepts(delta, temperature, rng) {
+                    neighborhood.apply_transition(state, &mut solution);
+                    // moves such as relocation change the sizes of the transitions
+                    neighborhood.reset(&solution, rng);
+                    current += delta;
+                    if current < best_solution.0 {
+                        best_solution = (current, solution.deep_clone());
+                    }
+                }
+            }
+            progress.iteration();
+            if progress.iterations.is_multiple_of(self.epoch.max(1)) {
+                stagnation = if best_solution.0 < epoch_best { 0 } else { stagnation + 1 };
+                epoch_best = best_solution.0;
+                temperature = self.cooling.next(temperature, initial, stagnation);
+            }
+        }
+        best_solution.1
+    }
+}
+
+impl Solver<TSPInstance, TSPSolution> for SimulatedAnnealingSolver {
+    fn solve(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng) -> TSPSolution {
+        self.solve_observed(start_vertex, instance, rng, &mut NoObserver)
+    }
+
+    fn solve_observed(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng, observer: &mut dyn Observer) -> TSPSolution {
+        let solution = self.initial_solver.solve(start_vertex, instance, rng);
+        self.anneal(instance, solution, rng, observer)
+    }
+
+    fn solve_s(&self, _start_vertex: usize, instance: &TSPInstance, solution: TSPSolution, rng: &mut StdRng) -> TSPSolution {
+        self.anneal(instance, solution, rng, &mut NoObserver)
+    }
+
+    fn supports(&self, instance: &TSPInstance) -> bool {
+        self.initial_solver.supports(instance)
+    }
+}
@@ -1,8 +1,10 @@
-use crate::traits::Solver;
+use crate::traits::{Solver, Cost};
 use crate::tsp::def::{TSPInstance, TSPSolution};
 use crate::tsp::partial_path::PartialPath;
 use crate::tsp::picker::Picker;
+use crate::tsp::selection::Selection;
 use rand::Rng;
+use rand::rngs::StdRng;
 
 
 pub struct GreedySolver {
@@ -16,10 +18,9 @@ impl GreedySolver {
         }
     }
 
-    fn remote_random(instance: &TSPInstance) -> (f32, usize, usize) {
-        let mut max: (f32, usize, usize) = (-1., 0, 0);
+    fn remote_random(instance: &TSPInstance, rng: &mut StdRng) -> (Cost, usize, usize) {
+        let mut max: (Cost, usize, usize) = (-1, 0, 0);
         let n: usize = instance.dimension;
-        let mut rng = rand::thread_rng();
         let i = rng.gen_range(0..n);
         for j in i + 1..n {
             let dist = instance.dist_k(i, j);
@@ -30,85 +31,123 @@ impl GreedySolver {
         max
     }
 
-    fn remote(start_vertex: usize, instance: &TSPInstance) -> (f32, usize, usize) {
-        let mut max: (f32, usize, usize) = (-1., 0, 0);
+    // start_vertex followed by vertices chosen one by one as the farthest from those already selected
+    fn remote(start_vertex: usize, instance: &TSPInstance) -> Vec<usize> {
         let n: usize = instance.dimension;
-        for j in 0..n {
-            if j == start_vertex {
-                continue;
+        let mut seeds = vec![start_vertex];
+        while seeds.len() < instance.no_cycles {
+            let mut max: (Cost, usize) = (-1, 0);
+            for j in 0..n {
+                if seeds.contains(&j) {
+                    continue;
+                }
+                let dist = seeds.iter()
+                    .map(|seed| instance.dist_k(*seed, j))
+                    .min().unwrap();
+                if dist > max.0 {
+                    max = (dist, j);
+                }
             }
-            let dist = instance.dist_k(start_vertex, j);
-            if dist > max.0 {
-                max = (dist, start_vertex, j);
+            seeds.push(max.1);
+        }
+        seeds
+    }
+
+    // index of the path furthest below its target size, the first one on ties,
+    // paths without room for any remaining demand are only chosen when all of them are full
+    fn most_lacking(partial_paths: &[PartialPath], targets: &[usize], visited: &[bool]) -> usize {
+        let key = |i: usize| (partial_paths[i].has_room(visited), targets[i] as isize - partial_paths[i].vec.len() as isize);
+        let mut max = 0;
+        for i in 1..partial_paths.len() {
+            if key(i) > key(max) {
+                max = i;
             }
         }
         max
     }
+
+    // in depot mode every cycle is rotated to start at the depot
+    fn finish(instance: &TSPInstance, partial_paths: Vec<PartialPath>) -> TSPSolution {
+        TSPSolution::with_dimension(partial_paths.into_iter().map(|mut partial_path| {
+            if let Some(depot) = instance.depot {
+                let position = partial_path.vec.iter().position(|v| *v == depot).unwrap();
+                partial_path.vec.rotate_left(position);
+            }
+            partial_path.vec
+        }).collect(), instance.dimension)
+    }
+
+    fn reached(instance: &TSPInstance, visited: &[bool]) -> bool {
+        let vertices = (0..visited.len()).filter(|v| visited[*v]);
+        let profit = vertices.clone().map(|v| instance.prize(v)).sum();
+        instance.selection.reached(instance.dimension, vertices.count(), profit)
+    }
+
+    fn complete(&self, instance: &TSPInstance, partial_paths: &mut [PartialPath], visited: &mut [bool]) {
+        let k = partial_paths.len();
+        let targets = instance.targets();
+        let single = instance.capacity.is_some() || matches!(instance.selection, Selection::Profit(_));
+        let mut remaining = instance.selection.size(instance.dimension)
+            .saturating_sub(visited.iter().filter(|v| **v).count());
+        while remaining > 0 && !GreedySolver::reached(instance, visited)
+            && (0..k).any(|i| targets[i] + partial_paths[0].vec.len() != targets[0] + partial_paths[i].vec.len()) {
+            self.picker.add(GreedySolver::most_lacking(partial_paths, &targets, visited), partial_paths, visited);
+            remaining -= 1;
+        }
+        while remaining >= k && !single {
+            self.picker.add_all(partial_paths, visited);
+            remaining -= k;
+        }
+        while remaining > 0 && !GreedySolver::reached(instance, visited) {
+            self.picker.add(GreedySolver::most_lacking(partial_paths, &targets, visited), partial_paths, visited);
+            remaining -= 1;
+        }
+    }
 }
 
 impl Solver<TSPInstance, TSPSolution> for GreedySolver {
-    fn solve(&self, start_vertex: usize, instance: &TSPInstance) -> TSPSolution {
-        let max = GreedySolver::remote(start_vertex, instance);
+    fn solve(&self, start_vertex: usize, instance: &TSPInstance, _rng: &mut StdRng) -> TSPSolution {
         let n: usize = instance.dimension;
         let mut visited = vec![false; n];
-        visited[max.1] = true;
-        visited[max.2] = true;
-        let mut partial_a = PartialPath {
-            instance: &instance,
-            vec: vec![max.1],
-        };
-        let mut partial_b = PartialPath {
-            instance: &instance,
-            vec: vec![max.2],
+        let mut partial_paths = Vec::new();
+        let seeds = match instance.depot {
+            Some(depot) => vec![depot; instance.no_cycles],
+            None => GreedySolver::remote(start_vertex, instance),
         };
-        while (partial_a.vec.len() < partial_b.vec.len()) {
-            self.picker.add(&mut partial_a, &mut partial_b, &mut visited);
-        }
-        while (partial_b.vec.len() > partial_a.vec.len()) {
-            self.picker.add(&mut partial_b, &mut partial_a, &mut visited);
-        }
-        while partial_a.vec.len() + partial_b.vec.len() < n {
-            self.picker.add_both(&mut partial_a, &mut partial_b, &mut visited);
+        for seed in seeds {
+            visited[seed] = true;
+            partial_paths.push(PartialPath {
+                instance,
+                vec: vec![seed],
+            });
         }
+        self.complete(instance, &mut partial_paths, &mut visited);
 
-        TSPSolution::new(
-            partial_a.vec,
-            partial_b.vec,
-        )
+        GreedySolver::finish(instance, partial_paths)
     }
 
-    fn solve_s(&self, start_vertex: usize, instance: &TSPInstance, solution: TSPSolution) -> TSPSolution {
+    fn solve_s(&self, _start_vertex: usize, instance: &TSPInstance, solution: TSPSolution, _rng: &mut StdRng) -> TSPSolution {
         let n: usize = instance.dimension;
         let mut visited = vec![false; n];
-        for val in solution.perm_a.iter() {
-            visited[*val] = true;
-        }
-        for val in solution.perm_b.iter() {
-            visited[*val] = true;
-        }
-        let mut partial_a = PartialPath {
-            instance: &instance,
-            vec: solution.perm_a,
-        };
-        let mut partial_b = PartialPath {
-            instance: &instance,
-            vec: solution.perm_b,
-        };
-
-        while (partial_a.vec.len() < partial_b.vec.len()) {
-            self.picker.add(&mut partial_a, &mut partial_b, &mut visited);
-        }
-        while (partial_b.vec.len() < partial_a.vec.len()) {
-            self.picker.add(&mut partial_b, &mut partial_a, &mut visited);
-        }
-
-        while partial_a.vec.len() + partial_b.vec.len() < n {
-            self.picker.add_both(&mut partial_a, &mut partial_b, &mut visited);
+        for perm in solution.perms.iter() {
+            for val in perm.iter() {
+                visited[*val] = true;
+            }
         }
+        let mut partial_paths: Vec<PartialPath> = solution.perms.into_iter().map(|mut perm| {
+            if let Some(depot) = instance.depot {
+                if !perm.contains(&depot) {
+                    perm.insert(0, depot);
+                }
+                visited[depot] = true;
+            }
+            PartialPath {
+                instance,
+                vec: perm,
+            }
+        }).collect();
+        self.complete(instance, &mut partial_paths, &mut visited);
 
-        TSPSolution::new(
-            partial_a.vec,
-            partial_b.vec,
-        )
+        GreedySolver::finish(instance, partial_paths)
     }
-}
\ No newline at end of file
+}
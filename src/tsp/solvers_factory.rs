@@ -1,8 +1,4 @@
 use crate::tsp::local_solvers::LocalRandomWalker;
-use crate::tsp::neighborhoods::vertex_transition::VertexTransition;
-use crate::tsp::neighborhoods::edges_transition::EdgesTransition;
-use crate::tsp::neighborhoods::transition::Transition;
-use crate::tsp::neighborhoods::inter_cycle_transition::InterCycleTransition;
 use crate::tsp::local_solvers::LocalGreedySolver;
 use crate::tsp::local_solvers::LocalSteepestSolver;
 use crate::tsp::multistart_solver::MultiStartSolver;
@@ -17,9 +13,11 @@ use crate::tsp::pickers::cycle_picker::CyclePicker;
 use crate::tsp::pickers::nearest_picker::NearestPicker;
 use json;
 use crate::tsp::random_solver::RandomSolver;
+use crate::tsp::tour_solver::TourSolver;
 use crate::tsp::solver::GreedySolver;
 use crate::tsp::candidate_solver::CandidateSolver;
 use crate::tsp::memory_solver::MemorySolver;
+use crate::tsp::lin_kernighan_solver::LinKernighanSolver;
 use crate::tsp::iterated_solver::{IteratedSolver, IteratedConstructionSolver};
 use crate::tsp::evolutionary_solver::EvolutionarySolver;
 use crate::tsp::convexity_checker::ConvexityChecker;
@@ -27,13 +25,45 @@ use crate::tsp::similarities::edge_similarity::EdgeSimilarity;
 use crate::tsp::similarities::vertex_similarity::VertexSimilarity;
 use crate::tsp::similarity::Similarity;
 use crate::tsp::custom_solver::CustomSolver;
+use crate::tsp::termination::Termination;
+use crate::tsp::migration::Migration;
+use crate::tsp::cooling::Cooling;
+use crate::tsp::simulated_annealing_solver::SimulatedAnnealingSolver;
+use crate::tsp::tabu_search_solver::TabuSearchSolver;
+use crate::tsp::variable_neighborhood_solver::{VariableNeighborhoodSolver, Shaking};
+use crate::tsp::neighborhoods::transition_kind::TransitionKind;
 
 pub struct SolversFactory;
 
 impl SolversFactory {
+    // Sets of transitions explored by the local searches and the metaheuristics, selected by the "transition" key
+    // of the solver config either by the name of a preset or given as a list of transitions in the format of TransitionKind
+    pub fn transitions(config: &json::JsonValue) -> Vec<TransitionKind> {
+        let name = match config["transition"].as_str() {
+            Some(name) => name,
+            None if config["transition"].is_null() => "Edges",
+            None => return TransitionKind::structure_from_json(&config["transition"]),
+        };
+        let num_neighbors = config["num_neighbors"].as_usize().unwrap_or(10);
+        let max_segment = TransitionKind::max_segment(config).unwrap_or(3);
+        let mut presets: HashMap<&str, Vec<TransitionKind>> = HashMap::new();
+        presets.insert("Vertex", vec![TransitionKind::InterCycle, TransitionKind::Vertex]);
+        presets.insert("Edges", vec![TransitionKind::InterCycle, TransitionKind::Edges]);
+        presets.insert("Relocate", vec![TransitionKind::InterCycle, TransitionKind::Relocate, TransitionKind::Edges]);
+        presets.insert("Selective", vec![TransitionKind::InterCycle, TransitionKind::Insert, TransitionKind::Remove, TransitionKind::Swap, TransitionKind::Edges]);
+        presets.insert("OrOpt", vec![TransitionKind::InterCycle, TransitionKind::OrOpt(max_segment), TransitionKind::Edges]);
+        presets.insert("ThreeOpt", vec![TransitionKind::InterCycle, TransitionKind::ThreeOpt(None)]);
+        presets.insert("ThreeOptNeighbors", vec![TransitionKind::InterCycle, TransitionKind::ThreeOpt(Some(num_neighbors))]);
+        presets.insert("Cross", vec![TransitionKind::InterCycle, TransitionKind::Cross(max_segment), TransitionKind::Edges]);
+        presets.remove(name).unwrap_or_else(|| panic!("Unknown transition {}", name))
+    }
+
     pub fn create_from_json(config: &json::JsonValue) -> Box<dyn Solver<TSPInstance, TSPSolution>> {
         if config["solver"] == "Random" {
             Box::new(RandomSolver)
+        } else if config["solver"] == "Tour" {
+            // the tour is read before any run starts
+            Box::new(TourSolver::new(config["file"].as_str().unwrap()).unwrap_or_else(|err| panic!("{}", err)))
         } else if config["solver"] == "Greedy" {
             let mut pickers: HashMap<&str, Box<dyn Picker>> = HashMap::new();
             pickers.insert("Nearest", Box::new(NearestPicker));
@@ -43,86 +73,148 @@ impl SolversFactory {
             let picker = pickers.remove(config["picker"].as_str().unwrap()).unwrap();
             Box::new(GreedySolver::new(picker))
         } else if config["solver"] == "Local" {
-            let mut transitions: HashMap<&str, fn() -> Vec<Box<dyn Transition>>> = HashMap::new();
-            transitions.insert("Vertex", || { vec![Box::new(InterCycleTransition {}), Box::new(VertexTransition {})] });
-            transitions.insert("Edges", || { vec![Box::new(InterCycleTransition {}), Box::new(EdgesTransition {})] });
             if config["type"] == "Greedy" {
-                Box::new(LocalGreedySolver::new(
+                let initial_solver = SolversFactory::create_from_json(&config["initial_solver"]);
+                let transition = SolversFactory::transitions(config);
+                match config["num_neighbors"].as_usize() {
+                    Some(num_neighbors) => Box::new(LocalGreedySolver::with_dont_look_bits(initial_solver, transition, num_neighbors)),
+                    None => Box::new(LocalGreedySolver::new(initial_solver, transition)),
+                }
+            } else if config["type"] == "Steepest" {
+                let initial_solver = SolversFactory::create_from_json(&config["initial_solver"]);
+                let transition = SolversFactory::transitions(config);
+                match config["num_neighbors"].as_usize() {
+                    Some(num_neighbors) => Box::new(LocalSteepestSolver::with_dont_look_bits(initial_solver, transition, num_neighbors)),
+                    None => Box::new(LocalSteepestSolver::new(initial_solver, transition)),
+                }
+            } else {
+                Box::new(LocalRandomWalker::new(
                     SolversFactory::create_from_json(&config["initial_solver"]),
-                    transitions.remove(config["transition"].as_str().unwrap()).unwrap(),
+                    SolversFactory::transitions(config),
                 ))
-            } else if config["type"] == "Steepest" {
-                Box::new(LocalSteepestSolver::new(
+            }
+        } else if config["solver"] == "Candidate" {
+            if config["dont_look_bits"] == true {
+                Box::new(CandidateSolver::with_dont_look_bits(
+                    config["num_neighbors"].as_usize().unwrap(),
                     SolversFactory::create_from_json(&config["initial_solver"]),
-                    transitions.remove(config["transition"].as_str().unwrap()).unwrap(),
                 ))
             } else {
-                Box::new(LocalRandomWalker::new(
+                Box::new(CandidateSolver::new(
+                    config["num_neighbors"].as_usize().unwrap(),
                     SolversFactory::create_from_json(&config["initial_solver"]),
-                    transitions.remove(config["transition"].as_str().unwrap()).unwrap(),
                 ))
             }
-        } else if config["solver"] == "Candidate" {
-            Box::new(CandidateSolver::new(
-                config["num_neighbors"].as_usize().unwrap(),
-                SolversFactory::create_from_json(&config["initial_solver"]),
-            ))
         } else if config["solver"] == "Memory" {
-            Box::new(MemorySolver::new(
-                SolversFactory::create_from_json(&config["initial_solver"])
+            match TransitionKind::max_segment(config) {
+                Some(max_segment) => Box::new(MemorySolver::with_cross_exchange(
+                    SolversFactory::create_from_json(&config["initial_solver"]),
+                    max_segment,
+                )),
+                None => Box::new(MemorySolver::new(
+                    SolversFactory::create_from_json(&config["initial_solver"])
+                )),
+            }
+        } else if config["solver"] == "LinKernighan" {
+            Box::new(LinKernighanSolver::new(
+                SolversFactory::create_from_json(&config["initial_solver"]),
+                config["num_neighbors"].as_usize().unwrap_or(10),
+                config["max_depth"].as_usize().unwrap_or(10),
+                config["inter_cycle"].as_bool().unwrap_or(true),
             ))
         } else if config["solver"] == "MSLS" {
+            let no_iterations = config["no_iterations"].as_usize().unwrap();
+            assert!(no_iterations > 0, "MSLS needs at least one iteration");
             Box::new(MultiStartSolver::new(
                 SolversFactory::create_from_json(&config["sub_solver"]),
-                config["no_iterations"].as_usize().unwrap(),
+                no_iterations,
+                config["threads"].as_usize().unwrap_or(1),
             ))
         } else if config["solver"] == "Iterated" {
-            let mut transitions: HashMap<&str, fn() -> Vec<Box<dyn Transition>>> = HashMap::new();
-            transitions.insert("Vertex", || { vec![Box::new(InterCycleTransition {}), Box::new(VertexTransition {})] });
-            transitions.insert("Edges", || { vec![Box::new(InterCycleTransition {}), Box::new(EdgesTransition {})] });
             Box::new(IteratedSolver::new(
                 SolversFactory::create_from_json(&config["initial_solver"]),
                 SolversFactory::create_from_json(&config["sub_solver"]),
-                config["time"].as_f32().unwrap(),
+                Termination::from_solver_json(config),
                 config["perturb_min"].as_f32().unwrap(),
                 config["perturb_max"].as_f32().unwrap(),
-                || vec![Box::new(InterCycleTransition {}), Box::new(EdgesTransition {})],
+                SolversFactory::transitions(config),
             ))
         } else if config["solver"] == "IteratedConstruction" {
-            let mut transitions: HashMap<&str, fn() -> Vec<Box<dyn Transition>>> = HashMap::new();
-            transitions.insert("Vertex", || { vec![Box::new(InterCycleTransition {}), Box::new(VertexTransition {})] });
-            transitions.insert("Edges", || { vec![Box::new(InterCycleTransition {}), Box::new(EdgesTransition {})] });
             Box::new(IteratedConstructionSolver::new(
                 SolversFactory::create_from_json(&config["initial_solver"]),
                 SolversFactory::create_from_json(&config["sub_solver"]),
-                config["time"].as_f32().unwrap(),
+                Termination::from_solver_json(config),
                 config["perturb_min"].as_f32().unwrap(),
                 config["perturb_max"].as_f32().unwrap(),
-                || vec![Box::new(InterCycleTransition {}), Box::new(EdgesTransition {})],
+                SolversFactory::transitions(config),
             ))
 
             // initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
             // time: f32,
             // population_size: usize,
             // transition: fn() -> Vec<Box<dyn Transition>>
+        } else if config["solver"] == "SimulatedAnnealing" {
+            Box::new(SimulatedAnnealingSolver::new(
+                SolversFactory::create_from_json(&config["initial_solver"]),
+                Termination::from_solver_json(config),
+                Cooling::from_json(&config["cooling"]),
+                config["epoch"].as_usize().unwrap_or(100),
+                config["initial_acceptance"].as_f64().unwrap_or(0.5),
+                config["samples"].as_usize().unwrap_or(100),
+                SolversFactory::transitions(config),
+            ))
+        } else if config["solver"] == "TabuSearch" {
+            Box::new(TabuSearchSolver::new(
+                SolversFactory::create_from_json(&config["initial_solver"]),
+                Termination::from_solver_json(config),
+                (config["tenure_min"].as_usize().unwrap_or(5), config["tenure_max"].as_usize().unwrap_or(15)),
+                config["candidates"].as_usize().unwrap_or(200),
+                config["stagnation"].as_usize().unwrap_or(1000),
+                config["perturbation"].as_usize().unwrap_or(10),
+                SolversFactory::transitions(config),
+            ))
+        } else if config["solver"] == "VND" || config["solver"] == "VNS" {
+            // neighborhood structures in the order of the descent, each one a transition or a list of them
+            let neighborhoods: Vec<Vec<TransitionKind>> = config["neighborhoods"].members()
+                .map(TransitionKind::structure_from_json)
+                .collect();
+            assert!(!neighborhoods.is_empty(), "{} needs at least one neighborhood", config["solver"]);
+            let initial_solver = SolversFactory::create_from_json(&config["initial_solver"]);
+            let steepest = config["type"] == "Steepest";
+            if config["solver"] == "VNS" {
+                Box::new(VariableNeighborhoodSolver::with_shaking(initial_solver, neighborhoods, steepest, Shaking {
+                    termination: Termination::from_solver_json(config),
+                    min: config["shake_min"].as_usize().unwrap_or(1),
+                    step: config["shake_step"].as_usize().unwrap_or(1),
+                    max: config["shake_max"].as_usize().unwrap_or(10),
+                }))
+            } else {
+                Box::new(VariableNeighborhoodSolver::new(initial_solver, neighborhoods, steepest))
+            }
         } else if config["solver"] == "Evolutionary" {
             Box::new(EvolutionarySolver::new(
                 SolversFactory::create_from_json(&config["local_solver"]),
                 SolversFactory::create_from_json(&config["construction_solver"]),
-                config["time"].as_f32().unwrap(),
+                Termination::from_solver_json(config),
                 config["population_size"].as_usize().unwrap(),
                 config["steps_to_mutation"].as_usize().unwrap(),
-                || vec![Box::new(InterCycleTransition {}), Box::new(EdgesTransition {})],
+                SolversFactory::transitions(config),
             ))
         } else if config["solver"] == "Custom" {
+            let no_populations = config["no_populations"].as_usize().unwrap();
+            let population_size = config["population_size"].as_usize().unwrap();
+            // crossing over needs two candidates on every island
+            assert!(no_populations > 0 && population_size >= 2 * no_populations,
+                    "Custom needs at least two candidates for each of its {} islands, population_size is {}", no_populations, population_size);
             Box::new(CustomSolver::new(
                 SolversFactory::create_from_json(&config["local_solver"]),
                 SolversFactory::create_from_json(&config["construction_solver"]),
-                config["time"].as_f32().unwrap(),
-                config["no_populations"].as_usize().unwrap(),
-                config["population_size"].as_usize().unwrap(),
+                Termination::from_solver_json(config),
+                no_populations,
+                population_size,
                 config["steps_to_mutation"].as_usize().unwrap(),
-                || vec![Box::new(InterCycleTransition {}), Box::new(EdgesTransition {})],
+                Migration::from_json(&config["migration"]),
+                SolversFactory::transitions(config),
             ))
         } else if config["solver"] == "Convexity" {
             let similarity: Box<dyn Similarity> = if config["similarity"] == "Edge" {
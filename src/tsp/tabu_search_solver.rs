@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use rand::Rng;
+use rand::rngs::StdRng;
+
+use crate::traits::{Instance, Solver, Cost, Observer, NoObserver};
+use crate::tsp::def::{TSPInstance, TSPSolution};
+use crate::tsp::neighborhoods::neighborhood::Neighborhood;
+use crate::tsp::neighborhoods::transition_kind::TransitionKind;
+use crate::tsp::termination::{Termination, Progress};
+
+// Applies the best move of a random candidate list even if it makes the solution worse. Undoing a recent move
+// is tabu for a random tenure: the edges a move removed cannot be added back and the vertices it moved cannot
+// return to their cycles, unless that gives a new best solution. After stagnation iterations without a new best
+// the search restarts from the best solution with a few random moves.
+pub struct TabuSearchSolver {
+    initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
+    termination: Termination,
+    // range of the number of iterations for which a move cannot be undone
+    tenure: (usize, usize),
+    candidates: usize,
+    stagnation: usize,
+    perturbation: usize,
+    transition: Vec<TransitionKind>,
+}
+
+// Edges removed and added by a move and the vertices it moved as (vertex, cycle it left, cycle it entered)
+#[derive(Debug, Default)]
+struct Attributes {
+    removed: Vec<(usize, usize)>,
+    added: Vec<(usize, usize)>,
+    moved: Vec<(usize, usize, usize)>,
+}
+
+// Iteration until which an attribute stays tabu
+#[derive(Debug, Default)]
+struct TabuList {
+    edges: HashMap<(usize, usize), usize>,
+    assignments: HashMap<(usize, usize), usize>,
+}
+
+impl TabuList {
+    fn forbids(&self, attributes: &Attributes, iteration: usize) -> bool {
+        attributes.added.iter().any(|edge| self.edges.get(edge).is_some_and(|until| *until > iteration))
+            || attributes.moved.iter().any(|(vertex, _, to)| self.assignments.get(&(*vertex, *to)).is_some_and(|until| *until > iteration))
+    }
+
+    fn forbid(&mut self, attributes: &Attributes, until: usize) {
+        for edge in attributes.removed.iter() {
+            self.edges.insert(*edge, until);
+        }
+        for (vertex, from, _) in attributes.moved.iter() {
+            self.assignments.insert((*vertex, *from), until);
+        }
+    }
+}
+
+impl TabuSearchSolver {
+    pub fn new(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
+               termination: Termination,
+               tenure: (usize, usize),
+               candidates: usize,
+               stagnation: usize,
+               perturbation: usize,
+               transition: Vec<TransitionKind>) -> TabuSearchSolver {
+        assert!(tenure.0 <= tenure.1, "TabuSearch needs tenure_min <= tenure_max, got {} and {}", tenure.0, tenure.1);
+        TabuSearchSolver {
+            initial_solver,
+            termination,
+            tenure,
+            candidates,
+            stagnation,
+            perturbation,
+            transition,
+        }
+    }
+
+    // Sorted edges of the given cycles, unordered when the direction does not matter
+    fn edges(&self, instance: &TSPInstance, solution: &TSPSolution, cycles: &[usize]) -> Vec<(usize, usize)> {
+        let mut edges = vec![];
+        for perm in cycles.iter().map(|cycle| &solution.perms[*cycle]) {
+            for id in 0..perm.len() {
+                let (a, b) = (perm[id], perm[(id+1)%perm.len()]);
+                edges.push(if instance.symmetric { (a.min(b), a.max(b)) } else { (a, b) });
+            }
+        }
+        edges.sort_unstable();
+        edges
+    }
+
+    // Edges of the first sorted list that are not in the second one
+    fn missing(&self, edges: &[(usize, usize)], other: &[(usize, usize)]) -> Vec<(usize, usize)> {
+        let mut missing = vec![];
+        let mut j = 0;
+        for edge in edges.iter() {
+            while j < other.len() && other[j] < *edge {
+                j += 1;
+            }
+            if j < other.len() && other[j] == *edge {
+                j += 1;
+            } else {
+                missing.push(*edge);
+            }
+        }
+        missing
+    }
+
+    // Attributes of the move that turned solution into next, only the cycles it changed are compared
+    fn attributes(&self, instance: &TSPInstance, solution: &TSPSolution, next: &TSPSolution, changed: &[usize]) -> Attributes {
+        let edges = self.edges(instance, solution, changed);
+        let next_edges = self.edges(instance, next, changed);
+        let mut moved: Vec<(usize, usize, usize)> = changed.iter()
+            .flat_map(|cycle| solution.perms[*cycle].iter().chain(next.perms[*cycle].iter()))
+            .filter(|vertex| solution.cycle[**vertex] != next.cycle[**vertex])
+            .map(|vertex| (*vertex, solution.cycle[*vertex], next.cycle[*vertex]))
+            .collect();
+        moved.sort_unstable();
+        moved.dedup();
+        Attributes {
+            removed: self.missing(&edges, &next_edges),
+            added: self.missing(&next_edges, &edges),
+            moved,
+        }
+    }
+
+    // Undoes the changes of a rejected move on the scratch copy, touching only the cycles it changed
+    fn restore(&self, next: &mut TSPSolution, solution: &TSPSolution, changed: &[usize]) {
+        for cycle in changed.iter() {
+            for vertex in next.perms[*cycle].iter().chain(solution.perms[*cycle].iter()) {
+                next.cycle[*vertex] = solution.cycle[*vertex];
+                next.order[*vertex] = solution.order[*vertex];
+            }
+            next.perms[*cycle].clone_from(&solution.perms[*cycle]);
+        }
+    }
+
+    fn candidate_list(&self, instance: &TSPInstance, solution: &TSPSolution, current: Cost, neighborhood: &mut Neighborhood, progress: &mut Progress, rng: &mut StdRng) -> Vec<(Cost, usize)> {
+        let mut candidates = vec![];
+        for _ in 0..self.candidates {
+            let state = neighborhood.next_random(rng);
+            if let Some(delta) = neighborhood.score(state, instance, solution) {
+                progress.evaluation(current + delta);
+                candidates.push((delta, state));
+            }
+        }
+        candidates.sort_by_key(|(delta, _)| *delta);
+        candidates
+    }
+
+    // The best solution changed by up to perturbation random moves
+    fn diversify(&self, instance: &TSPInstance, mut solution: TSPSolution, neighborhood: &mut Neighborhood, rng: &mut StdRng) -> TSPSolution {
+        neighborhood.reset(&solution, rng);
+        for _ in 0..self.perturbation {
+            let state = neighborhood.next_random(rng);
+            if neighborhood.score(state, instance, &solution).is_some() {
+                neighborhood.apply_transition(state, &mut solution);
+                neighborhood.reset(&solution, rng);
+            }
+        }
+        solution
+    }
+
+    fn search(&self, instance: &TSPInstance, mut solution: TSPSolution, rng: &mut StdRng, observer: &mut dyn Observer) -> TSPSolution {
+        let mut progress = Progress::observed(observer);
+        let mut current = instance.eval(&solution);
+        progress.evaluation(current);
+        let mut best_solution: (Cost, TSPSolution) = (current, solution.deep_clone());
+        let mut neighborhood = Neighborhood::new(TransitionKind::create_all(&self.transition), &solution, true, rng);
+        // moves are tried on a scratch copy that is restored when they are rejected, so no candidate is cloned
+        let mut next = solution.deep_clone();
+        let mut tabu = TabuList::default();
+        let mut stagnation = 0;
+        while !self.termination.reached(&progress) {
+            let iteration = progress.iterations;
+            for (delta, state) in self.candidate_list(instance, &solution, current, &mut neighborhood, &mut progress, rng) {
+                neighborhood.apply_transition(state, &mut next);
+                let changed: Vec<usize> = (0..solution.perms.len())
+                    .filter(|cycle| solution.perms[*cycle] != next.perms[*cycle])
+                    .collect();
+                let attributes = self.attributes(instance, &solution, &next, &changed);
+                // aspiration, a tabu move is allowed when it gives a new best solution
+                if current + delta >= best_solution.0 && tabu.forbids(&attributes, iteration) {
+                    self.restore(&mut next, &solution, &changed);
+                    continue;
+                }
+                tabu.forbid(&attributes, iteration + 1 + rng.gen_range(self.tenure.0..=self.tenure.1));
+                neighborhood.apply_transition(state, &mut solution);
+                current += delta;
+                neighborhood.reset(&solution, rng);
+                break;
+            }
+            progress.iteration();
+            if current < best_solution.0 {
+                best_solution = (current, solution.deep_clone());
+                stagnation = 0;
+            } else {
+                stagnation += 1;
+            }
+            if stagnation >= self.stagnation {
+                solution = self.diversify(instance, best_solution.1.deep_clone(), &mut neighborhood, rng);
+                current = instance.eval(&solution);
+                progress.evaluation(current);
+                next = solution.deep_clone();
+                tabu = TabuList::default();
+                stagnation = 0;
+            }
+        }
+        best_solution.1
+    }
+}
+
+impl Solver<TSPInstance, TSPSolution> for TabuSearchSolver {
+    fn solve(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng) -> TSPSolution {
+        self.solve_observed(start_vertex, instance, rng, &mut NoObserver)
+    }
+
+    fn solve_observed(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng, observer: &mut dyn Observer) -> TSPSolution {
+        let solution = self.initial_solver.solve(start_vertex, instance, rng);
+        self.search(instance, solution, rng, observer)
+    }
+
+    fn solve_s(&self, _start_vertex: usize, instance: &TSPInstance, solution: TSPSolution, rng: &mut StdRng) -> TSPSolution {
+        self.search(instance, solution, rng, &mut NoObserver)
+    }
+
+    fn supports(&self, instance: &TSPInstance) -> bool {
+        self.initial_solver.supports(instance)
+    }
+}
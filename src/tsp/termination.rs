@@ -0,0 +1,146 @@
+use std::time::{Duration, Instant};
+use json;
+
+use crate::traits::{Cost, Observer};
+
+// Criteria are checked against the progress of a single search, the islands of CustomSolver each check them against
+// their own, so an iteration or evaluation budget is given to every island
+#[derive(Debug, Clone, PartialEq)]
+pub enum Termination {
+    // milliseconds
+    WallTime(f32),
+    CpuTime(f32),
+    Iterations(usize),
+    Evaluations(usize),
+    Target(Cost),
+    // iterations without an improvement of the best score
+    Stagnation(usize),
+    Any(Vec<Termination>),
+    All(Vec<Termination>),
+}
+
+impl Termination {
+    pub fn from_json(config: &json::JsonValue) -> Termination {
+        if config["type"] == "WallTime" {
+            Termination::WallTime(config["ms"].as_f32().unwrap())
+        } else if config["type"] == "CpuTime" {
+            Termination::CpuTime(config["ms"].as_f32().unwrap())
+        } else if config["type"] == "Iterations" {
+            Termination::Iterations(config["count"].as_usize().unwrap())
+        } else if config["type"] == "Evaluations" {
+            Termination::Evaluations(config["count"].as_usize().unwrap())
+        } else if config["type"] == "Target" {
+            Termination::Target(config["score"].as_i64().unwrap())
+        } else if config["type"] == "Stagnation" {
+            Termination::Stagnation(config["iterations"].as_usize().unwrap())
+        } else if config["type"] == "Any" {
+            Termination::Any(config["criteria"].members().map(Termination::from_json).collect())
+        } else if config["type"] == "All" {
+            Termination::All(config["criteria"].members().map(Termination::from_json).collect())
+        } else {
+            panic!("Unknown termination criterion {}", config["type"])
+        }
+    }
+
+    // Solver configs either describe their stopping criterion under "termination" or give a "time" in milliseconds
+    pub fn from_solver_json(config: &json::JsonValue) -> Termination {
+        if config["termination"].is_null() {
+            Termination::WallTime(config["time"].as_f32().unwrap())
+        } else {
+            Termination::from_json(&config["termination"])
+        }
+    }
+
+    pub fn reached(&self, progress: &Progress) -> bool {
+        match self {
+            Termination::WallTime(ms) => progress.start.elapsed().as_secs_f32() * 1000.0 >= *ms,
+            Termination::CpuTime(ms) => progress.cpu_elapsed().as_secs_f32() * 1000.0 >= *ms,
+            Termination::Iterations(count) => progress.iterations >= *count,
+            Termination::Evaluations(count) => progress.evaluations >= *count,
+            Termination::Target(score) => progress.best <= *score,
+            Termination::Stagnation(iterations) => progress.since_improvement >= *iterations,
+            Termination::Any(criteria) => criteria.iter().any(|criterion| criterion.reached(progress)),
+            Termination::All(criteria) => criteria.iter().all(|criterion| criterion.reached(progress)),
+        }
+    }
+}
+
+// What a search did so far, termination criteria are checked against it
+pub struct Progress<'a> {
+    start: Instant,
+    cpu_start: Duration,
+    pub iterations: usize,
+    pub evaluations: usize,
+    pub best: Cost,
+    since_improvement: usize,
+    observer: Option<&'a mut dyn Observer>,
+}
+
+impl<'a> Progress<'a> {
+    pub fn new() -> Progress<'a> {
+        Progress {
+            start: Instant::now(),
+            cpu_start: cpu_time(),
+            iterations: 0,
+            evaluations: 0,
+            best: Cost::MAX,
+            since_improvement: 0,
+            observer: None,
+        }
+    }
+
+    pub fn observed(observer: &'a mut dyn Observer) -> Progress<'a> {
+        Progress {
+            observer: Some(observer),
+            ..Progress::new()
+        }
+    }
+
+    pub fn iteration(&mut self) {
+        self.iterations += 1;
+        self.since_improvement += 1;
+    }
+
+    pub fn evaluation(&mut self, score: Cost) {
+        self.evaluations += 1;
+        if score < self.best {
+            self.best = score;
+            self.since_improvement = 0;
+            if let Some(observer) = self.observer.as_mut() {
+                observer.improved(self.start.elapsed(), self.iterations, score);
+            }
+        }
+    }
+
+    // CPU time of the calling thread, so that solvers running side by side do not count each other's work
+    #[cfg(unix)]
+    fn cpu_elapsed(&self) -> Duration {
+        cpu_time() - self.cpu_start
+    }
+
+    // without a thread clock the wall time stands in for it
+    #[cfg(not(unix))]
+    fn cpu_elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+impl Default for Progress<'_> {
+    fn default() -> Self {
+        Progress::new()
+    }
+}
+
+#[cfg(unix)]
+fn cpu_time() -> Duration {
+    let mut time = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    unsafe {
+        libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time);
+    }
+    Duration::new(time.tv_sec as u64, time.tv_nsec as u32)
+}
+
+#[cfg(not(unix))]
+fn cpu_time() -> Duration {
+    Duration::ZERO
+}
@@ -0,0 +1,153 @@
+use std::fs;
+use std::io;
+use std::io::Write;
+
+use crate::traits::Instance;
+use crate::parse_error::{ParseError, ParseErrorKind};
+use crate::tsp::def::{TSPInstance, TSPSolution, parse_number};
+
+// Every cycle is written as a separate tour terminated by -1, with the shared depot at its front
+pub fn write_tour(file: &mut impl Write, name: &str, instance: &TSPInstance, solution: &TSPSolution) -> io::Result<()> {
+    writeln!(file, "{} : {}", NAME, name)?;
+    writeln!(file, "{} : Length {}", COMMENT, instance.eval(solution))?;
+    writeln!(file, "{} : TOUR", TYPE)?;
+    writeln!(file, "{} : {}", DIMENSION, instance.dimension)?;
+    writeln!(file, "{}", TOUR_SECTION)?;
+    for perm in solution.perms.iter() {
+        for vertex in perm.iter() {
+            writeln!(file, "{}", vertex + 1)?;
+        }
+        writeln!(file, "-1")?;
+    }
+    writeln!(file, "{}", EOF)
+}
+
+// Tours of a file as they were read, one list of 1-based node ids per tour, every id with its line
+pub struct Tour {
+    file_name: String,
+    dimension: Option<(usize, usize)>,
+    tours: Vec<Vec<(usize, usize)>>,
+    last_line: usize,
+}
+
+// Reads tours written by write_tour or any TSPLIB tour file with one tour per cycle
+pub fn read_tour(file_name: &str) -> Result<Tour, ParseError> {
+    let cts = fs::read_to_string(file_name)
+        .map_err(|err| ParseError::new(file_name, None, ParseErrorKind::Io(err)))?;
+    let mut tour = Tour {
+        file_name: String::from(file_name),
+        dimension: None,
+        tours: Vec::new(),
+        last_line: 0,
+    };
+    let mut current: Vec<(usize, usize)> = Vec::new();
+    let mut in_section = false;
+
+    for (line_i, line) in cts.lines().enumerate() {
+        let line_no = line_i + 1;
+        tour.last_line = line_no;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line == EOF {
+            break;
+        }
+        if in_section {
+            for token in line.split_whitespace() {
+                if token == "-1" {
+                    if !current.is_empty() {
+                        tour.tours.push(current);
+                        current = Vec::new();
+                    }
+                    continue;
+                }
+                current.push((parse_number(token, file_name, line_no)?, line_no));
+            }
+            continue;
+        }
+        let split: Vec<&str> = line.splitn(2, ':').collect::<Vec<&str>>();
+        let (k, v) = if split.len() > 1 {
+            (split[0].trim(), split[1].trim())
+        } else {
+            (split[0].trim(), split[0].trim())
+        };
+        match k {
+            TYPE if v != "TOUR" => {
+                return Err(ParseError::at(file_name, line_no, ParseErrorKind::Unsupported(TYPE, String::from(v))));
+            }
+            DIMENSION => {
+                tour.dimension = Some((parse_number(v, file_name, line_no)?, line_no));
+            }
+            TOUR_SECTION => {
+                in_section = true;
+            }
+            _ => ()
+        }
+    }
+    if !current.is_empty() {
+        tour.tours.push(current);
+    }
+
+    if !in_section {
+        return Err(ParseError::new(file_name, None, ParseErrorKind::MissingKey(TOUR_SECTION)));
+    }
+    Ok(tour)
+}
+
+// Reads the tours and checks that they form a solution of the instance
+pub fn parse_tour(file_name: &str, instance: &TSPInstance) -> Result<TSPSolution, ParseError> {
+    read_tour(file_name)?.solution(instance)
+}
+
+impl Tour {
+    // The solution of the instance the tours form, an error if they do not form one
+    pub fn solution(&self, instance: &TSPInstance) -> Result<TSPSolution, ParseError> {
+        let file_name = self.file_name.as_str();
+        let n = instance.dimension;
+        if let Some((dimension, line_no)) = self.dimension {
+            if dimension != n {
+                return Err(ParseError::at(file_name, line_no, ParseErrorKind::WrongCount("nodes", n, dimension)));
+            }
+        }
+        let mut perms: Vec<Vec<usize>> = Vec::new();
+        let mut seen = vec![false; n];
+        for tour in self.tours.iter() {
+            let mut perm: Vec<usize> = Vec::new();
+            for (node, line_no) in tour.iter() {
+                if *node == 0 || *node > n {
+                    return Err(ParseError::at(file_name, *line_no, ParseErrorKind::InvalidNode(node.to_string())));
+                }
+                let vertex = node - 1;
+                let depot_again = instance.depot == Some(vertex) && !perm.contains(&vertex);
+                if seen[vertex] && !depot_again {
+                    return Err(ParseError::at(file_name, *line_no, ParseErrorKind::InvalidNode(node.to_string())));
+                }
+                seen[vertex] = true;
+                perm.push(vertex);
+            }
+            perms.push(perm);
+        }
+
+        if perms.len() != instance.no_cycles {
+            return Err(ParseError::at(file_name, self.last_line, ParseErrorKind::WrongCount("tours", instance.no_cycles, perms.len())));
+        }
+        if let Some(depot) = instance.depot {
+            for perm in perms.iter_mut() {
+                let position = perm.iter().position(|vertex| *vertex == depot).ok_or_else(|| ParseError::at(
+                    file_name, self.last_line, ParseErrorKind::MissingValue(format!("depot {} in every tour", depot + 1))))?;
+                perm.rotate_left(position);
+            }
+        }
+        let solution = TSPSolution::with_dimension(perms, n);
+        let visited = instance.visited(&solution);
+        if !instance.selection.is_selective() && visited != n {
+            return Err(ParseError::at(file_name, self.last_line, ParseErrorKind::WrongCount("nodes", n, visited)));
+        }
+        Ok(solution)
+    }
+}
+
+def_str_consts! {
+    NAME, COMMENT, TYPE, DIMENSION, TOUR_SECTION, EOF
+}
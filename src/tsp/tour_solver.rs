@@ -0,0 +1,34 @@
+use crate::tsp::def::TSPSolution;
+use crate::tsp::def::TSPInstance;
+use rand::rngs::StdRng;
+use crate::traits::Solver;
+use crate::parse_error::ParseError;
+use crate::tsp::tour::{Tour, read_tour};
+
+// Returns a solution saved earlier as a .tour file, e.g. as a starting point for local search.
+// The file is read when the solver is built, instances the tours do not fit are not supported.
+pub struct TourSolver {
+    tour: Tour,
+}
+
+impl TourSolver {
+    pub fn new(file_name: &str) -> Result<TourSolver, ParseError> {
+        Ok(TourSolver {
+            tour: read_tour(file_name)?
+        })
+    }
+}
+
+impl Solver<TSPInstance, TSPSolution> for TourSolver {
+    fn solve(&self, _: usize, instance: &TSPInstance, _: &mut StdRng) -> TSPSolution {
+        self.tour.solution(instance).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn solve_s(&self, start_vertex: usize, instance: &TSPInstance, _: TSPSolution, rng: &mut StdRng) -> TSPSolution {
+        self.solve(start_vertex, instance, rng)
+    }
+
+    fn supports(&self, instance: &TSPInstance) -> bool {
+        self.tour.solution(instance).is_ok()
+    }
+}
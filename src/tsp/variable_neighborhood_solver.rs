@@ -0,0 +1,152 @@
+use rand::rngs::StdRng;
+
+use crate::traits::{Instance, Solver, Cost, Observer, NoObserver};
+use crate::tsp::def::{TSPInstance, TSPSolution};
+use crate::tsp::neighborhoods::neighborhood::Neighborhood;
+use crate::tsp::neighborhoods::transition_kind::TransitionKind;
+use crate::tsp::termination::{Termination, Progress};
+
+// Variable neighborhood descent over an ordered list of neighborhood structures: the search moves on to the next
+// structure when the current one has no improving move and goes back to the first one after every improvement.
+// With shaking it becomes VNS, which repeatedly descends from the best solution changed by random moves.
+pub struct VariableNeighborhoodSolver {
+    initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>,
+    neighborhoods: Vec<Vec<TransitionKind>>,
+    steepest: bool,
+    shaking: Option<Shaking>,
+}
+
+// The number of random moves of a shaking starts at min and grows by step after every descent that did not find
+// a better solution, going back to min after an improvement or once it would exceed max
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shaking {
+    pub termination: Termination,
+    pub min: usize,
+    pub step: usize,
+    pub max: usize,
+}
+
+impl Shaking {
+    fn next(&self, strength: usize, improved: bool) -> usize {
+        if improved || strength + self.step > self.max {
+            self.min
+        } else {
+            strength + self.step
+        }
+    }
+}
+
+impl VariableNeighborhoodSolver {
+    pub fn new(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>, neighborhoods: Vec<Vec<TransitionKind>>, steepest: bool) -> VariableNeighborhoodSolver {
+        VariableNeighborhoodSolver {
+            initial_solver,
+            neighborhoods,
+            steepest,
+            shaking: None,
+        }
+    }
+
+    pub fn with_shaking(initial_solver: Box<dyn Solver<TSPInstance, TSPSolution>>, neighborhoods: Vec<Vec<TransitionKind>>, steepest: bool, shaking: Shaking) -> VariableNeighborhoodSolver {
+        VariableNeighborhoodSolver {
+            initial_solver,
+            neighborhoods,
+            steepest,
+            shaking: Some(shaking),
+        }
+    }
+
+    fn structure(&self, kinds: &[TransitionKind], solution: &TSPSolution, rng: &mut StdRng) -> Neighborhood {
+        Neighborhood::new(TransitionKind::create_all(kinds), solution, !self.steepest, rng)
+    }
+
+    // The first improving move of the neighborhood, or the best one for the steepest descent
+    fn improving_move(&self, neighborhood: &mut Neighborhood, instance: &TSPInstance, solution: &TSPSolution, rng: &mut StdRng) -> Option<usize> {
+        neighborhood.reset(solution, rng);
+        let mut best: (Cost, Option<usize>) = (0, None);
+        while let Some((score, state)) = neighborhood.next(instance, solution) {
+            if score < best.0 {
+                best = (score, Some(state));
+                if !self.steepest {
+                    break;
+                }
+            }
+        }
+        best.1
+    }
+
+    fn descend(&self, instance: &TSPInstance, mut solution: TSPSolution, neighborhoods: &mut [Neighborhood], rng: &mut StdRng) -> TSPSolution {
+        let mut k = 0;
+        while k < neighborhoods.len() {
+            match self.improving_move(&mut neighborhoods[k], instance, &solution, rng) {
+                Some(state) => {
+                    neighborhoods[k].apply_transition(state, &mut solution);
+                    k = 0;
+                }
+                None => k += 1,
+            }
+        }
+        solution
+    }
+
+    // Up to strength random moves from all the neighborhood structures
+    fn shake(&self, instance: &TSPInstance, mut solution: TSPSolution, neighborhood: &mut Neighborhood, strength: usize, rng: &mut StdRng) -> TSPSolution {
+        neighborhood.reset(&solution, rng);
+        for _ in 0..strength {
+            let state = neighborhood.next_random(rng);
+            if neighborhood.score(state, instance, &solution).is_some() {
+                neighborhood.apply_transition(state, &mut solution);
+                neighborhood.reset(&solution, rng);
+            }
+        }
+        solution
+    }
+
+    fn search(&self, instance: &TSPInstance, solution: TSPSolution, rng: &mut StdRng, observer: &mut dyn Observer) -> TSPSolution {
+        let mut progress = Progress::observed(observer);
+        let mut neighborhoods: Vec<Neighborhood> = self.neighborhoods.iter()
+            .map(|kinds| self.structure(kinds, &solution, rng))
+            .collect();
+        let solution = self.descend(instance, solution, &mut neighborhoods, rng);
+        let mut best_solution: (Cost, TSPSolution) = (instance.eval(&solution), solution);
+        progress.evaluation(best_solution.0);
+        let shaking = match &self.shaking {
+            Some(shaking) => shaking,
+            None => return best_solution.1,
+        };
+        let all: Vec<TransitionKind> = self.neighborhoods.concat();
+        let mut neighborhood = self.structure(&all, &best_solution.1, rng);
+        let mut strength = shaking.min;
+        while !shaking.termination.reached(&progress) {
+            let solution = self.shake(instance, best_solution.1.deep_clone(), &mut neighborhood, strength, rng);
+            let solution = self.descend(instance, solution, &mut neighborhoods, rng);
+            let score = instance.eval(&solution);
+            progress.evaluation(score);
+            progress.iteration();
+            let improved = score < best_solution.0;
+            if improved {
+                best_solution = (score, solution);
+            }
+            strength = shaking.next(strength, improved);
+        }
+        best_solution.1
+    }
+}
+
+impl Solver<TSPInstance, TSPSolution> for VariableNeighborhoodSolver {
+    fn solve(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng) -> TSPSolution {
+        self.solve_observed(start_vertex, instance, rng, &mut NoObserver)
+    }
+
+    fn solve_observed(&self, start_vertex: usize, instance: &TSPInstance, rng: &mut StdRng, observer: &mut dyn Observer) -> TSPSolution {
+        let solution = self.initial_solver.solve(start_vertex, instance, rng);
+        self.search(instance, solution, rng, observer)
+    }
+
+    fn solve_s(&self, _start_vertex: usize, instance: &TSPInstance, solution: TSPSolution, rng: &mut StdRng) -> TSPSolution {
+        self.search(instance, solution, rng, &mut NoObserver)
+    }
+
+    fn supports(&self, instance: &TSPInstance) -> bool {
+        self.initial_solver.supports(instance)
+    }
+}
@@ -4,36 +4,38 @@ use std::{fs};
 use std::fs::File;
 use std::io::prelude::*;
 use json;
+use crate::traits::{Cost, Observer};
+use std::fmt::Debug;
+use std::ops::AddAssign;
 
 use rand::Rng;
+use rand::rngs::StdRng;
 use std::cmp::Ordering;
+use std::time::Duration;
 
-#[derive(PartialEq)]
-pub struct MinFloat(pub f32);
+// Reverses the ordering of costs, so that a BinaryHeap keyed by it pops the smallest one
+#[derive(PartialEq, Eq)]
+pub struct MinCost(pub Cost);
 
-impl Eq for MinFloat {}
-
-impl PartialOrd for MinFloat {
+impl PartialOrd for MinCost {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        other.0.partial_cmp(&self.0)
+        Some(self.cmp(other))
     }
 }
 
-impl Ord for MinFloat {
-    fn cmp(&self, other: &MinFloat) -> Ordering {
-        self.partial_cmp(other).unwrap()
+impl Ord for MinCost {
+    fn cmp(&self, other: &MinCost) -> Ordering {
+        other.0.cmp(&self.0)
     }
 }
 
-pub fn random_combination(n: usize) -> (usize, usize) {
-    let mut rng = rand::thread_rng();
+pub fn random_combination(n: usize, rng: &mut StdRng) -> (usize, usize) {
     let x_1 = rng.gen_range(0..n);
     let x_2 = (rng.gen_range(1..n) + x_1) % n;
     return (x_1, x_2)
 }
 
-pub fn random_permutation(n: usize) -> Vec<usize> {
-    let mut rng = rand::thread_rng();
+pub fn random_permutation(n: usize, rng: &mut StdRng) -> Vec<usize> {
     let mut vec = Vec::with_capacity(n);
     for i in 0..n {
         vec.push(i);
@@ -45,28 +47,89 @@ pub fn random_permutation(n: usize) -> Vec<usize> {
     vec
 }
 
+// Assignment of rows to distinct columns maximizing the total weight,
+// ties are resolved in favour of the lexicographically smallest assignment
+pub fn best_assignment(weights: &[Vec<usize>]) -> (usize, Vec<usize>) {
+    let k = weights.len();
+    let mut best = (0, (0..k).collect::<Vec<usize>>());
+    let mut current = Vec::with_capacity(k);
+    let mut used = vec![false; k];
+    search_assignment(weights, 0, &mut current, &mut used, &mut best);
+    best
+}
+
+fn search_assignment(weights: &[Vec<usize>], total: usize, current: &mut Vec<usize>, used: &mut [bool],
+                     best: &mut (usize, Vec<usize>)) {
+    let row = current.len();
+    if row == weights.len() {
+        if total > best.0 {
+            *best = (total, current.clone());
+        }
+        return;
+    }
+    let bound: usize = weights[row..].iter().map(|w| *w.iter().max().unwrap()).sum();
+    if total + bound <= best.0 {
+        return;
+    }
+    for column in 0..weights.len() {
+        if !used[column] {
+            used[column] = true;
+            current.push(column);
+            search_assignment(weights, total + weights[row][column], current, used, best);
+            current.pop();
+            used[column] = false;
+        }
+    }
+}
+
 pub fn contents(file_name: &str) -> String {
-    fs::read_to_string(file_name).expect("Something went wrong")
+    fs::read_to_string(file_name).unwrap_or_else(|err| panic!("Cannot read {}: {}", file_name, err))
+}
+
+// Values a Stat aggregates, sums are kept in the value type so that costs stay exact
+pub trait StatValue: Copy + PartialOrd + AddAssign + Debug {
+    const ZERO: Self;
+    const MIN: Self;
+    const MAX: Self;
+    fn as_f64(self) -> f64;
+}
+
+impl StatValue for f32 {
+    const ZERO: f32 = 0.0;
+    const MIN: f32 = f32::MIN;
+    const MAX: f32 = f32::MAX;
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl StatValue for Cost {
+    const ZERO: Cost = 0;
+    const MIN: Cost = Cost::MIN;
+    const MAX: Cost = Cost::MAX;
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
 }
 
 #[derive(Debug)]
-pub struct Stat {
-    min: f32,
-    max: f32,
-    sum: f32,
+pub struct Stat<T: StatValue> {
+    min: T,
+    max: T,
+    sum: T,
     count: usize
 }
 
-impl Stat {
-    pub fn new() -> Stat{
+impl<T: StatValue> Stat<T> {
+    pub fn new() -> Stat<T> {
         Stat {
-            min: f32::MAX,
-            max: 0.0,
-            sum: 0.0,
+            min: T::MAX,
+            max: T::MIN,
+            sum: T::ZERO,
             count: 0
         }
     }
-    pub fn update(&mut self, value: f32) -> bool {
+    pub fn update(&mut self, value: T) -> bool {
         let mut flag = false;
         if value < self.min {
             self.min = value;
@@ -79,27 +142,30 @@ impl Stat {
         self.count+=1;
         flag
     }
-    fn get_max(&self) -> f32 {
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+    fn get_max(&self) -> T {
         self.max
     }
-    fn get_min(&self) -> f32 {
+    fn get_min(&self) -> T {
         self.min
     }
-    fn get_avg(&self) -> f32 {
-        self.sum/(self.count as f32)
+    fn get_avg(&self) -> f64 {
+        self.sum.as_f64()/(self.count as f64)
     }
-    fn get(&self, name: &str) -> f32 {
+    fn get(&self, name: &str) -> f64 {
         if name == "min" {
-            self.get_min()
+            self.get_min().as_f64()
         } else if name == "max" {
-            self.get_max()
+            self.get_max().as_f64()
         } else {
             self.get_avg()
         }
     }
 }
 
-impl Clone for Stat {
+impl<T: StatValue> Clone for Stat<T> {
     fn clone(&self) -> Self {
         Stat {
             min: self.min,
@@ -110,7 +176,7 @@ impl Clone for Stat {
     }
 }
 
-pub fn print_table_to_file(file: &mut File, stats: &Vec<Vec<Stat>>, config: &json::JsonValue) {
+pub fn print_table_to_file<T: StatValue>(file: &mut File, stats: &Vec<Vec<Stat<T>>>, config: &json::JsonValue) {
     write!(file, "\\begin{{table}}[H]
     \\centering
     \\begin{{tabular}}{{|l|");
@@ -130,7 +196,7 @@ pub fn print_table_to_file(file: &mut File, stats: &Vec<Vec<Stat>>, config: &jso
 
     for i in 0..config["algorithms"].len() {
         for j in 0..config["instances"].len() {
-            if stats[i][j].get("avg") < stats[min_id[j]][j].get("avg") {
+            if stats[min_id[j]][j].is_empty() || stats[i][j].get("avg") < stats[min_id[j]][j].get("avg") {
                 min_id[j] = i;
             }
         }
@@ -141,6 +207,10 @@ pub fn print_table_to_file(file: &mut File, stats: &Vec<Vec<Stat>>, config: &jso
         
         for j in 0..config["instances"].len() {
             write!(file, " & ");
+            if stats[i][j].is_empty() {
+                write!(file, "--");
+                continue;
+            }
             if min_id[j] == i {
                 write!(file, "\\textbf{{");
             }
@@ -159,7 +229,67 @@ pub fn print_table_to_file(file: &mut File, stats: &Vec<Vec<Stat>>, config: &jso
     write!(file, "\n\n");
 }
 
+// Improvements of the best score during a single run of an algorithm
+pub struct Trace {
+    instance: String,
+    algorithm: String,
+    run: usize,
+    seed: u64,
+    points: Vec<(f32, usize, Cost)>,
+}
+
+impl Trace {
+    pub fn new(instance: &str, algorithm: &str, run: usize, seed: u64) -> Trace {
+        Trace {
+            instance: String::from(instance),
+            algorithm: String::from(algorithm),
+            run,
+            seed,
+            points: Vec::new(),
+        }
+    }
+}
+
+impl Observer for Trace {
+    fn improved(&mut self, elapsed: Duration, iteration: usize, score: Cost) {
+        self.points.push((elapsed.as_secs_f32() * 1000.0, iteration, score));
+    }
+}
+
+pub fn print_traces_to_csv(file: &mut File, traces: &[Trace]) -> std::io::Result<()> {
+    writeln!(file, "instance,algorithm,run,seed,ms,iteration,score")?;
+    for trace in traces.iter() {
+        for (ms, iteration, score) in trace.points.iter() {
+            writeln!(file, "{},{},{},{},{},{},{}", trace.instance, trace.algorithm, trace.run, trace.seed, ms, iteration, score)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn print_traces_to_json(file: &mut File, traces: &[Trace]) -> std::io::Result<()> {
+    let mut runs = json::JsonValue::new_array();
+    for trace in traces.iter() {
+        let mut points = json::JsonValue::new_array();
+        for (ms, iteration, score) in trace.points.iter() {
+            points.push(json::object! {"ms": *ms, "iteration": *iteration, "score": *score}).unwrap();
+        }
+        runs.push(json::object! {
+            "instance": trace.instance.as_str(),
+            "algorithm": trace.algorithm.as_str(),
+            "run": trace.run,
+            "seed": trace.seed,
+            "points": points,
+        }).unwrap();
+    }
+    write!(file, "{}", runs.pretty(2))
+}
+
+const COLORS: [&str; 8] = ["red", "blue", "green", "orange", "violet", "brown", "cyan", "magenta"];
+
 fn print_path(file: &mut File, color: &str, scale: f32, instance: &TSPInstance, perm: &Vec<usize>) {
+    if instance.cities.is_empty() {
+        return;
+    }
     for i in 0..perm.len() {
         let city_a_coord = instance.cities[perm[i]].get_coord();
         let city_b_coord = instance.cities[perm[(i+1)%perm.len()]].get_coord();
@@ -172,8 +302,9 @@ pub fn print_graph_to_file(file: &mut File, name: &str, scale: f32, instance: &T
     \\centering
     \\begin{{tikzpicture}}");
 
-    print_path(file, "red", scale, instance, &solution.perm_a);
-    print_path(file, "blue", scale, instance, &solution.perm_b);
+    for (c, perm) in solution.perms.iter().enumerate() {
+        print_path(file, COLORS[c % COLORS.len()], scale, instance, perm);
+    }
 
     write!(file, "\\end{{tikzpicture}}
     \\caption{{{}}}
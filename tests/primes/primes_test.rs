@@ -1,5 +1,7 @@
 #[cfg(test)]
 mod test {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
     use imo::primes::primes::Primes;
     #[test]
     fn test_is_prime() {
@@ -53,13 +55,13 @@ mod test {
 
     #[test]
     fn test_group_generator() {
-        check_group_generating(Primes::group_generator(13), 13);
-        check_group_generating(Primes::group_generator(2137), 2137);
+        check_group_generating(Primes::group_generator(13, &mut StdRng::seed_from_u64(0)), 13);
+        check_group_generating(Primes::group_generator(2137, &mut StdRng::seed_from_u64(0)), 2137);
     }
 
     #[test]
     fn test_group_generator_and_size() {
-        let (g, n) = Primes::group_generator_and_size(2135);
+        let (g, n) = Primes::group_generator_and_size(2135, &mut StdRng::seed_from_u64(0));
         assert_eq!(n, 2137);
         check_group_generating(g, n);
     }
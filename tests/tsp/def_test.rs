@@ -0,0 +1,201 @@
+#[cfg(test)]
+mod tests {
+    use imo::tsp::def::{TSPInstance, TSPSolution};
+    use imo::traits::{Instance, Cost};
+    use imo::tsp::edge_weight::{EdgeWeightType, EdgeWeightFormat};
+    use imo::tsp::distance::DistanceKind;
+    use imo::parse_error::{ParseError, ParseErrorKind};
+    use std::env;
+    use std::fs;
+
+    fn parse_str(name: &str, contents: &str) -> Result<TSPInstance, ParseError> {
+        let path = env::temp_dir().join(name);
+        fs::write(&path, contents).unwrap();
+        TSPInstance::parse_file(path.to_str().unwrap())
+    }
+
+    fn check_matrix(instance: &TSPInstance, expected: &Vec<Vec<Cost>>) {
+        assert_eq!(instance.dimension, expected.len());
+        for i in 0..expected.len() {
+            for j in 0..expected.len() {
+                assert_eq!(instance.dist_k(i, j), expected[i][j]);
+            }
+        }
+    }
+
+    #[test]
+    fn euc_2d_test() {
+        let instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
+        assert_eq!(instance.edge_weight_type, EdgeWeightType::Euc2d);
+        assert_eq!(instance.dist_k(0, 1), 1693);
+        assert_eq!(instance.dist_k(1, 0), 1693);
+    }
+
+    #[test]
+    fn explicit_test() {
+        let expected = vec![
+            vec![0, 3, 5, 9],
+            vec![3, 0, 4, 7],
+            vec![5, 4, 0, 6],
+            vec![9, 7, 6, 0],
+        ];
+        check_matrix(&TSPInstance::parse_file("data/sample_lower_diag.tsp").unwrap(), &expected);
+        check_matrix(&TSPInstance::parse_file("data/sample_upper_row.tsp").unwrap(), &expected);
+    }
+
+    #[test]
+    fn geo_test() {
+        let expected = vec![
+            vec![0, 509, 501],
+            vec![509, 0, 126],
+            vec![501, 126, 0],
+        ];
+        check_matrix(&TSPInstance::parse_file("data/sample_geo.tsp").unwrap(), &expected);
+    }
+
+    #[test]
+    fn att_test() {
+        let expected = vec![
+            vec![0, 1495, 381],
+            vec![1495, 0, 1135],
+            vec![381, 1135, 0],
+        ];
+        check_matrix(&TSPInstance::parse_file("data/sample_att.tsp").unwrap(), &expected);
+    }
+
+    #[test]
+    fn whitespace_test() {
+        let instance = parse_str("imo_whitespace.tsp",
+            "NAME : ws\nDIMENSION:3\nNODE_COORD_SECTION\n1\t0  0\n 2   3\t4\n3 0    4\nEOF\n").unwrap();
+        assert_eq!(instance.dist_k(0, 1), 5);
+        assert_eq!(instance.dist_k(1, 2), 3);
+    }
+
+    #[test]
+    fn unknown_section_test() {
+        let instance = parse_str("imo_unknown_section.tsp",
+            "DIMENSION : 3\nNODE_COORD_SECTION\n1 0 0\n2 3 4\n3 0 4\nFIXED_EDGES_SECTION\n1 2\n-1\nEDGE_DATA_SECTION\n2 3\nEOF\n").unwrap();
+        assert_eq!(instance.cities.len(), 3);
+        assert_eq!(instance.dist_k(0, 1), 5);
+    }
+
+    #[test]
+    fn missing_file_test() {
+        let err = TSPInstance::parse_file("data/missing.tsp").err().unwrap();
+        assert_eq!(err.line, None);
+        assert!(matches!(err.reason, ParseErrorKind::Io(_)));
+    }
+
+    #[test]
+    fn missing_dimension_test() {
+        let err = parse_str("imo_missing_dimension.tsp",
+            "NAME: broken\nNODE_COORD_SECTION\n1 0 0\nEOF\n").err().unwrap();
+        assert_eq!(err.line, Some(2));
+        assert!(matches!(err.reason, ParseErrorKind::MissingKey("DIMENSION")));
+    }
+
+    #[test]
+    fn empty_dimension_test() {
+        let err = parse_str("imo_empty_dimension.tsp",
+            "NAME: broken\nDIMENSION: 0\nEDGE_WEIGHT_TYPE: EXPLICIT\nEDGE_WEIGHT_FORMAT: UPPER_ROW\nEDGE_WEIGHT_SECTION\nEOF\n").err().unwrap();
+        assert_eq!(err.line, Some(2));
+        assert!(matches!(err.reason, ParseErrorKind::Unsupported("DIMENSION", _)));
+        assert_eq!(EdgeWeightFormat::UpperRow.size(0), 0);
+    }
+
+    #[test]
+    fn invalid_number_test() {
+        let err = parse_str("imo_invalid_number.tsp",
+            "NAME: broken\nDIMENSION: 2\nNODE_COORD_SECTION\n1 0 0\n2 1,5 0\nEOF\n").err().unwrap();
+        assert_eq!(err.line, Some(5));
+        assert!(matches!(err.reason, ParseErrorKind::InvalidNumber(_)));
+        assert!(format!("{}", err).ends_with("imo_invalid_number.tsp:5: invalid number '1,5'"));
+    }
+
+    #[test]
+    fn wrong_count_test() {
+        let err = parse_str("imo_wrong_count.tsp",
+            "NAME: broken\nDIMENSION: 3\nNODE_COORD_SECTION\n1 0 0\n2 1 0\nEOF\n").err().unwrap();
+        assert!(matches!(err.reason, ParseErrorKind::WrongCount(_, 3, 2)));
+    }
+
+    #[test]
+    fn cvrp_test() {
+        let instance = TSPInstance::parse_file("data/sample_cvrp.vrp").unwrap();
+        assert_eq!(instance.capacity, Some(55));
+        assert_eq!(instance.demands.len(), 16);
+        assert_eq!(instance.demand(0), 0);
+        assert_eq!(instance.demand(12), 11);
+        assert_eq!(instance.load(&(0..16).collect::<Vec<usize>>()), 93);
+        assert_eq!(instance.depots, vec![0]);
+
+        let mut instance = instance;
+        instance.depot = Some(0);
+        assert!(instance.check_capacity().is_ok());
+        instance.capacity = Some(40);
+        assert!(instance.check_capacity().is_err());
+        instance.capacity = Some(10);
+        assert!(instance.check_capacity().is_err());
+
+        let err = parse_str("imo_invalid_depot.vrp",
+            "DIMENSION : 2\nNODE_COORD_SECTION\n1 0 0\n2 1 1\nDEPOT_SECTION\n3\n-1\nEOF\n").unwrap_err();
+        assert_eq!(err.line, Some(6));
+        assert!(matches!(err.reason, ParseErrorKind::InvalidNode(_)));
+    }
+
+    #[test]
+    fn prize_test() {
+        let instance = parse_str("imo_prizes.tsp",
+            "DIMENSION : 3\nNODE_COORD_SECTION\n1 0 0\n2 3 4\n3 6 8\nPRIZE_SECTION\n1 0\n2 2\n3 7\nEOF\n").unwrap();
+        assert_eq!(instance.prizes, vec![0, 2, 7]);
+        assert_eq!(instance.prize(2), 7);
+
+        let err = parse_str("imo_fractional_prize.tsp",
+            "DIMENSION : 2\nNODE_COORD_SECTION\n1 0 0\n2 3 4\nPRIZE_SECTION\n1 0\n2 2.5\nEOF\n").unwrap_err();
+        assert!(matches!(err.reason, ParseErrorKind::InvalidNumber(_)));
+    }
+
+    #[test]
+    fn atsp_test() {
+        let instance = TSPInstance::parse_file("data/sample_atsp.atsp").unwrap();
+        assert!(!instance.symmetric);
+        assert_eq!(instance.dist_k(0, 1), 58);
+        assert_eq!(instance.dist_k(0, 0), 0);
+        assert_ne!(instance.dist_k(0, 1), instance.dist_k(1, 0));
+        assert!(TSPInstance::parse_file("data/kroA100.tsp").unwrap().symmetric);
+    }
+
+    #[test]
+    fn distance_test() {
+        for (file_name, kinds) in [
+            ("data/kroA100.tsp", vec![DistanceKind::Compact, DistanceKind::OnTheFly, DistanceKind::Dense]),
+            ("data/sample_atsp.atsp", vec![DistanceKind::Compact, DistanceKind::Dense]),
+        ] {
+            let dense = TSPInstance::parse_file(file_name).unwrap();
+            let mut instance = TSPInstance::parse_file(file_name).unwrap();
+            for kind in kinds {
+                instance.use_distance(kind).unwrap();
+                for a in 0..instance.dimension {
+                    for b in 0..instance.dimension {
+                        assert_eq!(instance.dist_k(a, b), dense.dist_k(a, b));
+                    }
+                }
+            }
+        }
+        let mut explicit = TSPInstance::parse_file("data/sample_atsp.atsp").unwrap();
+        assert!(explicit.use_distance(DistanceKind::OnTheFly).is_err());
+        assert_eq!(explicit.dist_k(0, 1), 58);
+        assert_eq!(DistanceKind::auto(100, false), DistanceKind::Dense);
+        assert_eq!(DistanceKind::auto(20000, false), DistanceKind::OnTheFly);
+        assert_eq!(DistanceKind::auto(20000, true), DistanceKind::Dense);
+    }
+
+    #[test]
+    fn exact_cost_test() {
+        // 16777217 is the first integer an f32 cannot represent
+        let instance = parse_str("imo_exact.tsp",
+            "DIMENSION : 4\nEDGE_WEIGHT_TYPE : EXPLICIT\nEDGE_WEIGHT_FORMAT : UPPER_ROW\nEDGE_WEIGHT_SECTION\n16777216 1 1\n1 1\n16777216\nEOF\n").unwrap();
+        let solution = TSPSolution::new(vec![vec![0, 1, 2, 3]]);
+        assert_eq!(instance.eval(&solution), 16777216 + 1 + 16777216 + 1);
+    }
+}
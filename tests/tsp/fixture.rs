@@ -0,0 +1,37 @@
+// Instances, observers and checks shared by the solver tests
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use imo::tsp::def::{TSPInstance, TSPSolution};
+use imo::traits::{Instance, Solver, Observer, Cost};
+use std::time::Duration;
+use imo::tsp::random_solver::RandomSolver;
+
+pub struct Scores(pub Vec<(usize, Cost)>);
+
+impl Observer for Scores {
+    fn improved(&mut self, _: Duration, iteration: usize, score: Cost) {
+        self.0.push((iteration, score));
+    }
+}
+
+pub fn instance() -> TSPInstance {
+    let mut instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
+    instance.no_cycles = 2;
+    instance
+}
+
+pub fn initial_score(instance: &TSPInstance) -> Cost {
+    instance.eval(&RandomSolver::new().solve(0, instance, &mut StdRng::seed_from_u64(3)))
+}
+
+// Solves twice from the same seed, the second time observed, checks that both runs give the same valid solution
+// and that the observer saw its score last
+pub fn observed_solve(solver: &dyn Solver<TSPInstance, TSPSolution>, instance: &TSPInstance) -> (TSPSolution, Scores) {
+    let solution = solver.solve(0, instance, &mut StdRng::seed_from_u64(3));
+    let mut scores = Scores(vec![]);
+    let observed = solver.solve_observed(0, instance, &mut StdRng::seed_from_u64(3), &mut scores);
+    assert!(solution.check());
+    assert_eq!(solution.perms, observed.perms);
+    assert_eq!(scores.0.last().unwrap().1, instance.eval(&solution));
+    (solution, scores)
+}
@@ -1,6 +1,8 @@
 
 #[cfg(test)]
 mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
     use imo::tsp::def::TSPInstance;
     use imo::traits::{Instance, Solver};
     use imo::tsp::solver::GreedySolver;
@@ -8,9 +10,9 @@ mod tests {
 
     #[test]
     fn greedy_test() {
-        let instance = TSPInstance::parse_file("data/kroA100.tsp");
+        let instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
         let solver = GreedySolver::new(Box::new(NearestPicker));
-        let solution = solver.solve(0, &instance);
+        let solution = solver.solve(0, &instance, &mut StdRng::seed_from_u64(0));
         println!("{}", instance.eval(&solution));
     }
 }
\ No newline at end of file
@@ -1,6 +1,8 @@
 
 #[cfg(test)]
 mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
     use imo::tsp::def::TSPInstance;
     use imo::traits::{Instance, Solver};
     use imo::tsp::random_solver::RandomSolver;
@@ -9,12 +11,16 @@ mod tests {
     use imo::tsp::pickers::cycle_picker::CyclePicker;
     use imo::tsp::pickers::regret_picker::RegretPicker;
     use imo::tsp::pickers::cycle_simultaneous_picker::CycleSimultaneousPicker;
+    use imo::tsp::picker::Picker;
+    use imo::tsp::balance::Balance;
+    use imo::tsp::selection::Selection;
 
-    fn check_all_nodes(perm_a: &Vec<usize>, perm_b: &Vec<usize>, n: usize) {
+    fn check_all_nodes(perms: &Vec<Vec<usize>>, n: usize) {
         let mut v: Vec<usize> = Vec::new();
-        assert_eq!(perm_a.len(), perm_b.len());
-        v.append(&mut perm_a.clone());
-        v.append(&mut perm_b.clone());
+        for perm in perms.iter() {
+            assert!(perm.len() <= perms[0].len() + 1 && perms[0].len() <= perm.len() + 1);
+            v.append(&mut perm.clone());
+        }
         v.sort();
         let mut j = 0;
         for i in 0..n {
@@ -25,46 +31,99 @@ mod tests {
 
     #[test]
     fn nearest_greedy_test() {
-        let instance = TSPInstance::parse_file("data/kroA100.tsp");
+        let instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
         let solver = GreedySolver::new(Box::new(NearestPicker));
-        let solution = solver.solve(0, &instance);
+        let solution = solver.solve(0, &instance, &mut StdRng::seed_from_u64(0));
         println!("Nearest: {}", instance.eval(&solution));
-        check_all_nodes(&solution.perm_a, &solution.perm_b, 100);
+        check_all_nodes(&solution.perms, 100);
     }
 
     #[test]
     fn cycle_greedy_test() {
-        let instance = TSPInstance::parse_file("data/kroA100.tsp");
+        let instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
         let solver = GreedySolver::new(Box::new(CyclePicker));
-        let solution = solver.solve(0, &instance);
+        let solution = solver.solve(0, &instance, &mut StdRng::seed_from_u64(0));
         println!("Cycle: {}", instance.eval(&solution));
-        check_all_nodes(&solution.perm_a, &solution.perm_b, 100);
+        check_all_nodes(&solution.perms, 100);
     }
 
     #[test]
     fn cycle_simultaneous_greedy_test() {
-        let instance = TSPInstance::parse_file("data/kroA100.tsp");
+        let instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
         let solver = GreedySolver::new(Box::new(CycleSimultaneousPicker));
-        let solution = solver.solve(0, &instance);
+        let solution = solver.solve(0, &instance, &mut StdRng::seed_from_u64(0));
         println!("Cycle: {}", instance.eval(&solution));
-        check_all_nodes(&solution.perm_a, &solution.perm_b, 100);
+        check_all_nodes(&solution.perms, 100);
     }
 
     #[test]
     fn regret_greedy_test() {
-        let instance = TSPInstance::parse_file("data/kroA100.tsp");
+        let instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
         let solver = GreedySolver::new(Box::new(RegretPicker));
-        let solution = solver.solve(0, &instance);
+        let solution = solver.solve(0, &instance, &mut StdRng::seed_from_u64(0));
         println!("Regret: {}", instance.eval(&solution));
-        check_all_nodes(&solution.perm_a, &solution.perm_b, 100);
+        check_all_nodes(&solution.perms, 100);
     }
 
     #[test]
     fn random_test() {
-        let instance = TSPInstance::parse_file("data/kroA100.tsp");
+        let instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
         let solver = RandomSolver::new();
-        let solution = solver.solve(0, &instance);
+        let solution = solver.solve(0, &instance, &mut StdRng::seed_from_u64(0));
         println!("{}", instance.eval(&solution));
-        check_all_nodes(&solution.perm_a, &solution.perm_b, 100);
+        check_all_nodes(&solution.perms, 100);
+    }
+
+    #[test]
+    fn k_cycles_greedy_test() {
+        let mut instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
+        instance.no_cycles = 3;
+        let pickers: Vec<Box<dyn Picker>> = vec![Box::new(NearestPicker), Box::new(CyclePicker),
+                                                 Box::new(CycleSimultaneousPicker), Box::new(RegretPicker)];
+        for picker in pickers {
+            let solution = GreedySolver::new(picker).solve(0, &instance, &mut StdRng::seed_from_u64(0));
+            assert_eq!(solution.perms.len(), 3);
+            assert!(solution.check());
+            check_all_nodes(&solution.perms, 100);
+        }
+        let solution = RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        assert_eq!(solution.perms.len(), 3);
+        check_all_nodes(&solution.perms, 100);
+    }
+
+    #[test]
+    fn exact_balance_greedy_test() {
+        let mut instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
+        instance.no_cycles = 3;
+        instance.balance = Balance::Exact(vec![50, 30, 20]);
+        let pickers: Vec<Box<dyn Picker>> = vec![Box::new(NearestPicker), Box::new(CyclePicker),
+                                                 Box::new(CycleSimultaneousPicker), Box::new(RegretPicker)];
+        for picker in pickers {
+            let solution = GreedySolver::new(picker).solve(0, &instance, &mut StdRng::seed_from_u64(0));
+            assert!(solution.check());
+            assert_eq!(solution.perms.iter().map(|perm| perm.len()).collect::<Vec<usize>>(), vec![50, 30, 20]);
+        }
+        let solution = RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        assert_eq!(solution.perms.iter().map(|perm| perm.len()).collect::<Vec<usize>>(), vec![50, 30, 20]);
+
+        assert_eq!(instance.check_balance(), Ok(()));
+        instance.depot = Some(0);
+        assert!(instance.check_balance().is_err());
+        instance.depot = None;
+        instance.no_cycles = 2;
+        assert!(instance.check_balance().is_err());
+
+        // a smaller selection scales the exact sizes instead of aborting construction
+        instance.no_cycles = 3;
+        instance.selection = Selection::Count(50);
+        assert_eq!(Balance::Exact(vec![50, 30, 20]).targets(11, 3), vec![6, 3, 2]);
+        let solution = RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        assert_eq!(solution.perms.iter().map(|perm| perm.len()).collect::<Vec<usize>>(), vec![25, 15, 10]);
+
+        instance.selection = Selection::All;
+        for (balance, valid) in [(Balance::Ratio(1.5), true), (Balance::Ratio(0.5), false), (Balance::Ratio(f32::NAN), false),
+                                 (Balance::Tolerance(0), false), (Balance::Tolerance(1), true)] {
+            assert_eq!(balance.check(100, 3).is_ok(), valid, "{:?}", balance);
+        }
     }
-}
\ No newline at end of file
+}
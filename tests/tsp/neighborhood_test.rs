@@ -0,0 +1,541 @@
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use imo::tsp::def::{TSPInstance, TSPSolution, UNVISITED};
+    use imo::traits::{Instance, Solver, Cost};
+    use imo::tsp::random_solver::RandomSolver;
+    use imo::tsp::memory_solver::MemorySolver;
+    use imo::tsp::lin_kernighan_solver::LinKernighanSolver;
+    use imo::tsp::neighborhoods::transition::Transition;
+    use imo::tsp::neighborhoods::edges_transition::EdgesTransition;
+    use imo::tsp::neighborhoods::vertex_transition::VertexTransition;
+    use imo::tsp::neighborhoods::inter_cycle_transition::InterCycleTransition;
+    use imo::tsp::neighborhoods::relocate_transition::RelocateTransition;
+    use imo::tsp::neighborhoods::or_opt_transition::OrOptTransition;
+    use imo::tsp::neighborhoods::three_opt_transition::ThreeOptTransition;
+    use imo::tsp::neighborhoods::cross_exchange_transition::CrossExchangeTransition;
+    use imo::tsp::local_solvers::{LocalSteepestSolver, LocalGreedySolver};
+    use imo::tsp::candidate_solver::CandidateSolver;
+    use imo::tsp::multistart_solver::MultiStartSolver;
+    use imo::tsp::custom_solver::CustomSolver;
+    use imo::tsp::evolutionary_solver::EvolutionarySolver;
+    use imo::tsp::migration::{Migration, Topology};
+    use imo::tsp::termination::Termination;
+    use imo::tsp::solver::GreedySolver;
+    use imo::tsp::pickers::regret_picker::RegretPicker;
+    use imo::tsp::pickers::nearest_picker::NearestPicker;
+    use imo::tsp::pickers::cycle_picker::CyclePicker;
+    use imo::tsp::pickers::cycle_simultaneous_picker::CycleSimultaneousPicker;
+    use imo::tsp::balance::Balance;
+    use imo::tsp::selection::Selection;
+    use imo::tsp::neighborhoods::insert_transition::InsertTransition;
+    use imo::tsp::neighborhoods::remove_transition::RemoveTransition;
+    use imo::tsp::neighborhoods::swap_transition::SwapTransition;
+    use imo::tsp::neighborhoods::transition_kind::TransitionKind;
+    use imo::tsp::solvers_factory::SolversFactory;
+    use imo::tsp::variable_neighborhood_solver::VariableNeighborhoodSolver;
+
+    fn instance(no_cycles: usize) -> TSPInstance {
+        let mut instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
+        instance.no_cycles = no_cycles;
+        instance
+    }
+
+    // applies every 7th move of the transition and compares its score with the change of the objective
+    // Vertices on both sides of every exclusive vertex, unordered when the direction does not matter
+    fn ends(instance: &TSPInstance, solution: &TSPSolution) -> Vec<Option<(usize, usize)>> {
+        let mut ends = vec![None; instance.dimension];
+        for perm in solution.perms.iter() {
+            let n = perm.len();
+            for id in 0..n {
+                let (prev, vertex, next) = (perm[(id+n-1)%n], perm[id], perm[(id+1)%n]);
+                if solution.is_exclusive(vertex) {
+                    ends[vertex] = Some(if instance.symmetric { (prev.min(next), prev.max(next)) } else { (prev, next) });
+                }
+            }
+        }
+        ends
+    }
+
+    // Applies every 7th move that scores, its delta has to match the change of the score and every vertex
+    // whose edges it changed has to be reported as touched
+    fn check_deltas(transition: &dyn Transition, instance: &TSPInstance, mut solution: TSPSolution) {
+        let size = transition.size(&solution);
+        for state in (1..=size).step_by(7) {
+            if let Some(delta) = transition.score(state, instance, &solution) {
+                let before = instance.eval(&solution);
+                let (touched, before_ends) = (transition.touched(state, instance, &solution), ends(instance, &solution));
+                transition.apply(state, &mut solution);
+                assert!(solution.check());
+                assert_eq!(instance.eval(&solution), before + delta);
+                for (vertex, (before, after)) in before_ends.iter().zip(ends(instance, &solution).iter()).enumerate() {
+                    assert!(before == after || after.is_none() || touched.contains(&vertex), "{} {:?} {:?} {:?}", vertex, before, after, touched);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn k_cycles_deltas_test() {
+        for no_cycles in 2..5 {
+            let instance = instance(no_cycles);
+            let solution = RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0));
+            check_deltas(&EdgesTransition::new(), &instance, solution.deep_clone());
+            check_deltas(&InterCycleTransition::new(), &instance, solution.deep_clone());
+            let size = VertexTransition::new().size(&solution);
+            assert_eq!(size, solution.perms.iter().map(|perm| perm.len() * (perm.len() - 1)).sum::<usize>());
+        }
+    }
+
+    #[test]
+    fn seeded_test() {
+        let instance = instance(2);
+        let solver = LocalGreedySolver::new(Box::new(RandomSolver::new()),
+            vec![TransitionKind::InterCycle, TransitionKind::Edges]);
+        let a = solver.solve(0, &instance, &mut StdRng::seed_from_u64(7));
+        let b = solver.solve(0, &instance, &mut StdRng::seed_from_u64(7));
+        let c = solver.solve(0, &instance, &mut StdRng::seed_from_u64(8));
+        assert_eq!(a.perms, b.perms);
+        assert_ne!(a.perms, c.perms);
+    }
+
+    #[test]
+    fn multistart_test() {
+        let instance = instance(2);
+        let solver = |threads| MultiStartSolver::new(Box::new(LocalGreedySolver::new(Box::new(RandomSolver::new()),
+            vec![TransitionKind::InterCycle, TransitionKind::Edges])), 6, threads);
+        let serial = solver(1).solve(0, &instance, &mut StdRng::seed_from_u64(5));
+        let parallel = solver(4).solve(0, &instance, &mut StdRng::seed_from_u64(5));
+        assert!(parallel.check());
+        assert_eq!(serial.perms, parallel.perms);
+        assert_eq!(solver(10).solve(0, &instance, &mut StdRng::seed_from_u64(5)).perms, serial.perms);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one iteration")]
+    fn multistart_iterations_test() {
+        SolversFactory::create_from_json(&json::parse(r#"{"solver": "MSLS", "no_iterations": 0, "sub_solver": {"solver": "Random"}}"#).unwrap());
+    }
+
+    #[test]
+    fn island_test() {
+        let instance = instance(2);
+        let mut rng = StdRng::seed_from_u64(0);
+        let migration = Migration { interval: 1, migrants: 2, topology: Topology::Full };
+        assert_eq!(migration.targets(1, 4, &mut rng), vec![0, 2, 3]);
+        assert_eq!(Migration::default().targets(3, 4, &mut rng), vec![0]);
+        assert!(Migration { topology: Topology::Random, ..Migration::default() }.targets(2, 4, &mut rng)[0] != 2);
+        assert!(!Migration { interval: 0, ..Migration::default() }.due(4));
+
+        for migration in [migration, Migration { topology: Topology::Random, ..Migration::default() }] {
+            let solver = CustomSolver::new(
+                Box::new(MemorySolver::new(Box::new(RandomSolver::new()))),
+                Box::new(GreedySolver::new(Box::new(RegretPicker))),
+                Termination::Iterations(30), 3, 12, 5, migration,
+                vec![TransitionKind::InterCycle, TransitionKind::Edges],
+            );
+            let solution = solver.solve(0, &instance, &mut StdRng::seed_from_u64(0));
+            assert!(solution.check());
+            assert!(instance.eval(&solution) < instance.eval(&RandomSolver::new().solve(0, &instance, &mut rng)));
+            // migrants are exchanged at fixed generations, so the run does not depend on thread timing
+            assert_eq!(solver.solve(0, &instance, &mut StdRng::seed_from_u64(0)).perms, solution.perms);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at least two candidates")]
+    fn island_population_test() {
+        SolversFactory::create_from_json(&json::parse(r#"{"solver": "Custom", "no_populations": 3, "population_size": 4,
+            "steps_to_mutation": 5, "time": 10, "local_solver": {"solver": "Random"}, "construction_solver": {"solver": "Random"}}"#).unwrap());
+    }
+
+    #[test]
+    fn k_cycles_memory_test() {
+        let instance = instance(4);
+        let solver = MemorySolver::new(Box::new(RandomSolver::new()));
+        let solution = solver.solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        assert_eq!(solution.perms.len(), 4);
+        assert!(solution.check());
+        assert!(instance.eval(&solution) < instance.eval(&RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0))));
+    }
+
+    #[test]
+    fn relocate_test() {
+        let even = instance(2);
+        let solution = RandomSolver::new().solve(0, &even, &mut StdRng::seed_from_u64(0));
+        assert!((1..=RelocateTransition::new().size(&solution))
+            .all(|state| RelocateTransition::new().score(state, &even, &solution).is_none()));
+
+        let mut instance = instance(3);
+        let solution = RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        instance.balance = Balance::Tolerance(10);
+        check_deltas(&RelocateTransition::new(), &instance, solution.deep_clone());
+
+        let solver = VariableNeighborhoodSolver::new(Box::new(RandomSolver::new()),
+            vec![vec![TransitionKind::Relocate], vec![TransitionKind::InterCycle]], true);
+        let solution = solver.solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        let sizes: Vec<usize> = solution.perms.iter().map(|perm| perm.len()).collect();
+        assert!(solution.check());
+        assert!(instance.balance.allows(&sizes));
+        assert_eq!(sizes.iter().sum::<usize>(), 100);
+    }
+
+    #[test]
+    fn or_opt_test() {
+        let mut instance = instance(3);
+        let solution = RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        let transition = OrOptTransition::new();
+        let size = transition.size(&solution);
+        assert!((1..=size).all(|state| transition.unpack_state(state, &solution).is_some()));
+        assert!(transition.unpack_state(size + 1, &solution).is_none());
+        check_deltas(&transition, &instance, solution.deep_clone());
+        check_deltas(&OrOptTransition::with_max_length(5), &instance, solution.deep_clone());
+
+        instance.balance = Balance::Tolerance(10);
+        check_deltas(&transition, &instance, solution.deep_clone());
+        let edges = LocalSteepestSolver::new(Box::new(RandomSolver::new()),
+            vec![TransitionKind::InterCycle, TransitionKind::Edges]);
+        let or_opt = LocalSteepestSolver::new(Box::new(RandomSolver::new()),
+            vec![TransitionKind::InterCycle, TransitionKind::OrOpt(3), TransitionKind::Edges]);
+        let solution = or_opt.solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        let sizes: Vec<usize> = solution.perms.iter().map(|perm| perm.len()).collect();
+        assert!(solution.check());
+        assert!(instance.balance.allows(&sizes));
+        assert!(instance.eval(&solution) < instance.eval(&edges.solve(0, &instance, &mut StdRng::seed_from_u64(0))));
+    }
+
+    #[test]
+    fn transitions_test() {
+        // presets, a list in the format of TransitionKind and the default
+        assert_eq!(SolversFactory::transitions(&json::parse(r#"{"transition": "Cross"}"#).unwrap()),
+                   vec![TransitionKind::InterCycle, TransitionKind::Cross(3), TransitionKind::Edges]);
+        assert_eq!(SolversFactory::transitions(&json::parse(r#"{"transition": "ThreeOptNeighbors", "num_neighbors": 6}"#).unwrap()),
+                   vec![TransitionKind::InterCycle, TransitionKind::ThreeOpt(Some(6))]);
+        assert_eq!(SolversFactory::transitions(&json::parse(r#"{"transition": ["Relocate", {"type": "ThreeOpt", "num_neighbors": 5}]}"#).unwrap()),
+                   vec![TransitionKind::Relocate, TransitionKind::ThreeOpt(Some(5))]);
+        assert_eq!(SolversFactory::transitions(&json::parse("{}").unwrap()), vec![TransitionKind::InterCycle, TransitionKind::Edges]);
+    }
+
+    #[test]
+    fn max_segment_test() {
+        let config = json::parse(r#"{"type": "OrOpt", "transition": "Cross", "max_segment": 5}"#).unwrap();
+        assert_eq!(TransitionKind::max_segment(&config), Some(5));
+        assert_eq!(TransitionKind::from_json(&config), TransitionKind::OrOpt(5));
+        assert_eq!(TransitionKind::from_json(&json::object!{"type": "Cross", "max_segment": 5}), TransitionKind::Cross(5));
+        assert_eq!(SolversFactory::transitions(&config), vec![TransitionKind::InterCycle, TransitionKind::Cross(5), TransitionKind::Edges]);
+        assert_eq!(SolversFactory::transitions(&json::object!{"transition": "OrOpt", "max_segment": 5}),
+                   vec![TransitionKind::InterCycle, TransitionKind::OrOpt(5), TransitionKind::Edges]);
+        // the memory solver reads the key the same way, a config without it keeps the plain queue
+        assert_eq!(TransitionKind::max_segment(&json::object!{"solver": "Memory"}), None);
+        let memory = SolversFactory::create_from_json(&json::object!{"solver": "Memory", "max_segment": 5, "initial_solver": {"solver": "Random"}});
+        let plain = MemorySolver::new(Box::new(RandomSolver::new()));
+        let instance = instance(2);
+        let solution = memory.solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        assert!(solution.check());
+        assert!(instance.eval(&solution) < instance.eval(&plain.solve(0, &instance, &mut StdRng::seed_from_u64(0))));
+    }
+
+    #[test]
+    fn three_opt_test() {
+        let instance = instance(2);
+        let solution = RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        let transition = ThreeOptTransition::new();
+        let size = transition.size(&solution);
+        assert_eq!(size, 2*(50*49*48/6)*7);
+        let triples: Vec<(usize, usize, usize, usize)> = (1..=size).step_by(7)
+            .map(|state| { let (cycle, i, j, k, _) = transition.unpack_state(state, &solution).unwrap(); (cycle, i, j, k) })
+            .collect();
+        assert!(triples.iter().all(|(_, i, j, k)| i < j && j < k && *k < 50));
+        assert!(triples.windows(2).all(|pair| pair[0] != pair[1]));
+        assert!(transition.unpack_state(size + 1, &solution).is_none());
+        check_deltas(&transition, &instance, solution.deep_clone());
+        check_deltas(&ThreeOptTransition::with_neighbors(5), &instance, solution.deep_clone());
+
+        // the 2-opt moves are among the reconnections, so a 3-opt local optimum is a 2-opt one as well
+        let three_opt = LocalGreedySolver::new(Box::new(RandomSolver::new()),
+            vec![TransitionKind::InterCycle, TransitionKind::ThreeOpt(None)]);
+        let solution = three_opt.solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        assert!(solution.check());
+        assert!((1..=EdgesTransition::new().size(&solution))
+            .all(|state| EdgesTransition::new().score(state, &instance, &solution).map_or(true, |delta| delta >= 0)));
+
+        let neighbors = LocalSteepestSolver::new(Box::new(RandomSolver::new()),
+            vec![TransitionKind::InterCycle, TransitionKind::ThreeOpt(Some(8))]);
+        let solution = neighbors.solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        assert!(solution.check());
+        assert!(instance.eval(&solution) < instance.eval(&RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0))));
+    }
+
+    #[test]
+    fn cross_exchange_test() {
+        let mut instance = instance(3);
+        let solution = RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        let transition = CrossExchangeTransition::new();
+        let size = transition.size(&solution);
+        // 3*(33+32+31) segments of three cycles of 34, 33 and 33 vertices, taken in pairs, in four orientations
+        let segments: Vec<usize> = solution.perms.iter().map(|perm| 3*perm.len() - 3).collect();
+        assert_eq!(size, 4*(segments[0]*segments[1] + segments[0]*segments[2] + segments[1]*segments[2]));
+        assert!((1..=size).all(|state| transition.unpack_state(state, &solution).is_some()));
+        assert!(transition.unpack_state(size + 1, &solution).is_none());
+        check_deltas(&transition, &instance, solution.deep_clone());
+        check_deltas(&CrossExchangeTransition::with_max_length(6), &instance, solution.deep_clone());
+
+        instance.balance = Balance::Tolerance(4);
+        check_deltas(&transition, &instance, solution.deep_clone());
+        let initial = instance.eval(&solution);
+        let memory = MemorySolver::with_cross_exchange(Box::new(RandomSolver::new()), 3);
+        let solution = memory.solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        let sizes: Vec<usize> = solution.perms.iter().map(|perm| perm.len()).collect();
+        assert!(solution.check());
+        assert!(instance.balance.allows(&sizes));
+        assert!(instance.eval(&solution) < initial);
+    }
+
+    #[test]
+    fn dont_look_bits_test() {
+        let instance = instance(2);
+        let neighbors = instance.nearest_neighbors(8);
+        let solution = RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        let transitions: Vec<Box<dyn Transition>> = vec![Box::new(InterCycleTransition::new()), Box::new(EdgesTransition::new()), Box::new(RelocateTransition::new())];
+        // every listed move puts the vertex next to one of its neighbors
+        for transition in transitions.iter() {
+            for vertex in 0..instance.dimension {
+                for state in transition.candidates(vertex, &neighbors[vertex], &solution) {
+                    let mut moved = solution.deep_clone();
+                    transition.apply(state, &mut moved);
+                    let perm = &moved.perms[moved.cycle[vertex]];
+                    let (prev, _, next) = transition.get_neighbors_in_cycle(moved.order[vertex], perm);
+                    assert!(neighbors[vertex].contains(&prev) || neighbors[vertex].contains(&next));
+                }
+            }
+        }
+
+        let edges = LocalSteepestSolver::with_dont_look_bits(Box::new(RandomSolver::new()),
+            vec![TransitionKind::InterCycle, TransitionKind::Edges], 8);
+        let solution = edges.solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        assert!(solution.check());
+        assert!(transitions[..2].iter().all(|transition| (0..instance.dimension)
+            .flat_map(|vertex| transition.candidates(vertex, &neighbors[vertex], &solution))
+            .all(|state| transition.score(state, &instance, &solution).is_none_or(|score| score >= 0))));
+        let candidate = CandidateSolver::with_dont_look_bits(8, Box::new(RandomSolver::new()));
+        assert_eq!(candidate.solve(0, &instance, &mut StdRng::seed_from_u64(0)).perms, solution.perms);
+
+        // transitions without candidate lists are still searched in full
+        let or_opt = LocalGreedySolver::with_dont_look_bits(Box::new(RandomSolver::new()),
+            vec![TransitionKind::InterCycle, TransitionKind::OrOpt(3), TransitionKind::Edges], 8);
+        let solution = or_opt.solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        assert!(solution.check());
+        let transition = OrOptTransition::new();
+        assert!((1..=transition.size(&solution)).all(|state| transition.score(state, &instance, &solution).is_none_or(|score| score >= 0)));
+    }
+
+    #[test]
+    fn lin_kernighan_test() {
+        let single = instance(1);
+        let edges = LocalSteepestSolver::new(Box::new(RandomSolver::new()), vec![TransitionKind::Edges]);
+        let lin_kernighan = LinKernighanSolver::new(Box::new(RandomSolver::new()), 10, 10, false);
+        let solution = lin_kernighan.solve(0, &single, &mut StdRng::seed_from_u64(0));
+        assert!(solution.check());
+        assert!(single.eval(&solution) < single.eval(&edges.solve(0, &single, &mut StdRng::seed_from_u64(0))));
+
+        // within the cycles only the order changes, across them the sizes stay balanced
+        let mut instance = instance(2);
+        instance.balance = Balance::Tolerance(4);
+        let initial = GreedySolver::new(Box::new(RegretPicker)).solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        let intra = lin_kernighan.solve_s(0, &instance, initial.deep_clone(), &mut StdRng::seed_from_u64(0));
+        assert!(intra.check());
+        assert_eq!(intra.cycle, initial.cycle);
+        assert!(instance.eval(&intra) < instance.eval(&initial));
+        let inter = LinKernighanSolver::new(Box::new(RandomSolver::new()), 10, 10, true)
+            .solve_s(0, &instance, initial.deep_clone(), &mut StdRng::seed_from_u64(0));
+        let sizes: Vec<usize> = inter.perms.iter().map(|perm| perm.len()).collect();
+        assert!(inter.check());
+        assert!(instance.balance.allows(&sizes));
+        assert!(instance.eval(&inter) <= instance.eval(&intra));
+    }
+
+    #[test]
+    fn capacity_test() {
+        let mut instance = TSPInstance::parse_file("data/sample_cvrp.vrp").unwrap();
+        instance.balance = Balance::Tolerance(16);
+        let solvers: Vec<Box<dyn Solver<TSPInstance, TSPSolution>>> = vec![
+            Box::new(RandomSolver::new()),
+            Box::new(GreedySolver::new(Box::new(NearestPicker))),
+            Box::new(GreedySolver::new(Box::new(CyclePicker))),
+            Box::new(GreedySolver::new(Box::new(CycleSimultaneousPicker))),
+            Box::new(GreedySolver::new(Box::new(RegretPicker))),
+            Box::new(LocalSteepestSolver::new(Box::new(GreedySolver::new(Box::new(RegretPicker))),
+                vec![TransitionKind::InterCycle, TransitionKind::Relocate, TransitionKind::Edges])),
+            Box::new(MemorySolver::new(Box::new(GreedySolver::new(Box::new(CyclePicker))))),
+            Box::new(CandidateSolver::new(5, Box::new(GreedySolver::new(Box::new(CyclePicker))))),
+        ];
+        for solver in solvers.iter() {
+            for start_vertex in 0..16 {
+                let solution = solver.solve(start_vertex, &instance, &mut StdRng::seed_from_u64(0));
+                assert!(solution.check());
+                assert!(instance.is_feasible(&solution));
+            }
+        }
+    }
+
+    #[test]
+    fn overload_test() {
+        let mut instance = TSPInstance::parse_file("data/sample_cvrp.vrp").unwrap();
+        instance.balance = Balance::Tolerance(16);
+        instance.depot = Some(0);
+        let overloaded = TSPSolution::with_dimension(vec![(0..14).collect(), vec![0, 14, 15]], 16);
+        assert!(!instance.is_feasible(&overloaded));
+        assert!(instance.eval(&overloaded) > instance.eval(&RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0))));
+        check_deltas(&InterCycleTransition::new(), &instance, overloaded.deep_clone());
+        check_deltas(&RelocateTransition::new(), &instance, overloaded.deep_clone());
+        check_deltas(&OrOptTransition::with_max_length(3), &instance, overloaded.deep_clone());
+        check_deltas(&CrossExchangeTransition::with_max_length(3), &instance, overloaded.deep_clone());
+        let solver = VariableNeighborhoodSolver::new(Box::new(RandomSolver::new()),
+            vec![vec![TransitionKind::Relocate], vec![TransitionKind::InterCycle]], true);
+        let solution = solver.solve_s(0, &instance, overloaded, &mut StdRng::seed_from_u64(0));
+        assert!(instance.is_feasible(&solution));
+    }
+
+    #[test]
+    fn depot_test() {
+        let mut instance = instance(3);
+        instance.depot = Some(5);
+        let anchored = |solution: &TSPSolution| {
+            assert!(solution.check());
+            assert!(solution.perms.iter().all(|perm| perm[0] == 5));
+            assert_eq!(solution.perms.iter().flatten().filter(|v| **v != 5).count(), 99);
+        };
+        let solvers: Vec<Box<dyn Solver<TSPInstance, TSPSolution>>> = vec![
+            Box::new(RandomSolver::new()),
+            Box::new(GreedySolver::new(Box::new(NearestPicker))),
+            Box::new(GreedySolver::new(Box::new(CyclePicker))),
+            Box::new(GreedySolver::new(Box::new(CycleSimultaneousPicker))),
+            Box::new(GreedySolver::new(Box::new(RegretPicker))),
+            Box::new(MemorySolver::new(Box::new(RandomSolver::new()))),
+            Box::new(MemorySolver::with_cross_exchange(Box::new(RandomSolver::new()), 3)),
+            Box::new(CandidateSolver::new(10, Box::new(RandomSolver::new()))),
+            Box::new(CandidateSolver::with_dont_look_bits(10, Box::new(RandomSolver::new()))),
+            Box::new(LinKernighanSolver::new(Box::new(RandomSolver::new()), 10, 10, true)),
+            Box::new(LocalGreedySolver::with_dont_look_bits(Box::new(RandomSolver::new()),
+                vec![TransitionKind::InterCycle, TransitionKind::Edges], 10)),
+        ];
+        for solver in solvers.iter() {
+            anchored(&solver.solve(0, &instance, &mut StdRng::seed_from_u64(0)));
+        }
+
+        instance.balance = Balance::Tolerance(10);
+        let solution = RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        check_deltas(&EdgesTransition::new(), &instance, solution.deep_clone());
+        check_deltas(&InterCycleTransition::new(), &instance, solution.deep_clone());
+        check_deltas(&RelocateTransition::new(), &instance, solution.deep_clone());
+        check_deltas(&OrOptTransition::new(), &instance, solution.deep_clone());
+        check_deltas(&ThreeOptTransition::new(), &instance, solution.deep_clone());
+        check_deltas(&CrossExchangeTransition::new(), &instance, solution.deep_clone());
+        let solver = LocalSteepestSolver::new(Box::new(RandomSolver::new()),
+            vec![TransitionKind::InterCycle, TransitionKind::Relocate, TransitionKind::Edges]);
+        anchored(&solver.solve(0, &instance, &mut StdRng::seed_from_u64(0)));
+    }
+
+    #[test]
+    fn selective_test() {
+        let mut instance = instance(2);
+        instance.selection = Selection::Ratio(0.5);
+        let pickers: Vec<Box<dyn Solver<TSPInstance, TSPSolution>>> = vec![
+            Box::new(RandomSolver::new()),
+            Box::new(GreedySolver::new(Box::new(NearestPicker))),
+            Box::new(GreedySolver::new(Box::new(CyclePicker))),
+            Box::new(GreedySolver::new(Box::new(CycleSimultaneousPicker))),
+            Box::new(GreedySolver::new(Box::new(RegretPicker))),
+            Box::new(MemorySolver::new(Box::new(GreedySolver::new(Box::new(RegretPicker))))),
+            Box::new(CandidateSolver::new(10, Box::new(RandomSolver::new()))),
+        ];
+        for solver in pickers.iter() {
+            let solution = solver.solve(0, &instance, &mut StdRng::seed_from_u64(0));
+            assert!(solution.check());
+            assert_eq!(instance.visited(&solution), 50);
+            assert_eq!(solution.cycle.iter().filter(|cycle| **cycle == UNVISITED).count(), 50);
+        }
+
+        // with a fixed count of vertices only swaps change which of them are visited
+        let initial = RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        check_deltas(&SwapTransition::new(), &instance, initial.deep_clone());
+        let solver = LocalSteepestSolver::new(Box::new(RandomSolver::new()),
+            vec![TransitionKind::Swap, TransitionKind::Edges]);
+        let solution = solver.solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        assert!(solution.check());
+        assert_eq!(instance.visited(&solution), 50);
+        assert!((0..100).any(|v| (solution.cycle[v] == UNVISITED) != (initial.cycle[v] == UNVISITED)));
+
+        instance.prizes = (0..100).map(|v| v as Cost * 37 % 500).collect();
+        instance.selection = Selection::Profit(10000);
+        instance.balance = Balance::Tolerance(100);
+        let solution = GreedySolver::new(Box::new(RegretPicker)).solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        assert!(instance.profit(&solution) >= 10000);
+        assert!(instance.visited(&solution) < 100);
+        check_deltas(&InsertTransition::new(), &instance, solution.deep_clone());
+        check_deltas(&RemoveTransition::new(), &instance, solution.deep_clone());
+        check_deltas(&SwapTransition::new(), &instance, solution.deep_clone());
+
+        let solver = LocalSteepestSolver::new(Box::new(RandomSolver::new()),
+            vec![TransitionKind::Insert, TransitionKind::Remove, TransitionKind::Edges]);
+        let solution = solver.solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        assert!(solution.check());
+        assert!(instance.profit(&solution) >= 10000);
+    }
+
+    fn asymmetric_instance() -> TSPInstance {
+        let mut instance = TSPInstance::parse_file("data/sample_atsp.atsp").unwrap();
+        instance.balance = Balance::Tolerance(4);
+        instance
+    }
+
+    #[test]
+    fn asymmetric_deltas_test() {
+        let mut instance = asymmetric_instance();
+        for depot in [None, Some(3)] {
+            instance.depot = depot;
+            for _ in 0..5 {
+                let solution = RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0));
+                check_deltas(&EdgesTransition::new(), &instance, solution.deep_clone());
+                check_deltas(&VertexTransition::new(), &instance, solution.deep_clone());
+                check_deltas(&InterCycleTransition::new(), &instance, solution.deep_clone());
+                check_deltas(&RelocateTransition::new(), &instance, solution.deep_clone());
+                check_deltas(&OrOptTransition::new(), &instance, solution.deep_clone());
+                check_deltas(&ThreeOptTransition::new(), &instance, solution.deep_clone());
+                check_deltas(&CrossExchangeTransition::new(), &instance, solution.deep_clone());
+            }
+        }
+        let solution = CandidateSolver::new(5, Box::new(RandomSolver::new())).solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        assert!(solution.check());
+    }
+
+    #[test]
+    fn asymmetric_memory_test() {
+        // an unsupported instance leaves the initial solution untouched
+        let instance = asymmetric_instance();
+        let initial = RandomSolver::new().solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        let solution = MemorySolver::new(Box::new(RandomSolver::new())).solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        assert_eq!(solution.perms, initial.perms);
+        let solution = LinKernighanSolver::new(Box::new(RandomSolver::new()), 5, 5, true).solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        assert_eq!(solution.perms, initial.perms);
+    }
+
+    #[test]
+    fn asymmetric_supports_test() {
+        let instance = asymmetric_instance();
+        let symmetric = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
+        assert!(CandidateSolver::new(5, Box::new(RandomSolver::new())).supports(&instance));
+        let memory = MultiStartSolver::new(Box::new(MemorySolver::new(Box::new(RandomSolver::new()))), 2, 1);
+        assert!(!memory.supports(&instance));
+        assert!(memory.supports(&symmetric));
+
+        // a local solver deep inside a metaheuristic refuses the instance for all of it
+        let evolutionary = EvolutionarySolver::new(
+            Box::new(LinKernighanSolver::new(Box::new(RandomSolver::new()), 5, 5, true)),
+            Box::new(RandomSolver::new()), Termination::Iterations(10), 10, 5,
+            vec![TransitionKind::InterCycle, TransitionKind::Edges]);
+        assert!(!evolutionary.supports(&instance));
+        assert!(evolutionary.supports(&symmetric));
+    }
+}
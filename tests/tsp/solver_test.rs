@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use imo::traits::Instance;
+    use imo::tsp::termination::Termination;
+    use imo::tsp::random_solver::RandomSolver;
+    use imo::tsp::simulated_annealing_solver::SimulatedAnnealingSolver;
+    use imo::tsp::cooling::Cooling;
+    use imo::tsp::tabu_search_solver::TabuSearchSolver;
+    use imo::tsp::variable_neighborhood_solver::{VariableNeighborhoodSolver, Shaking};
+    use imo::tsp::solvers_factory::SolversFactory;
+    use imo::tsp::neighborhoods::neighborhood::Neighborhood;
+    use imo::tsp::neighborhoods::transition_kind::TransitionKind;
+    use imo::tsp::balance::Balance;
+    use crate::tsp::fixture::{instance, initial_score, observed_solve};
+
+    #[test]
+    fn cooling_test() {
+        let adaptive = Cooling::from_json(&json::parse(r#"{"type": "Adaptive", "alpha": 0.5, "patience": 2, "reheat": 0.8}"#).unwrap());
+        assert_eq!(adaptive, Cooling::Adaptive { alpha: 0.5, patience: 2, reheat: 0.8 });
+        assert_eq!(adaptive.next(40.0, 100.0, 1), 20.0);
+        assert_eq!(adaptive.next(20.0, 100.0, 2), 80.0);
+        assert_eq!(Cooling::from_json(&json::JsonValue::Null), Cooling::Geometric(0.95));
+        let linear = Cooling::from_json(&json::parse(r#"{"type": "Linear", "fraction": 0.25}"#).unwrap());
+        assert_eq!(linear.next(100.0, 100.0, 0), 75.0);
+        assert_eq!(linear.next(10.0, 100.0, 0), 0.0);
+    }
+
+    #[test]
+    fn annealing_test() {
+        let mut instance = instance();
+        let solver = SimulatedAnnealingSolver::new(
+            Box::new(RandomSolver::new()),
+            Termination::Iterations(20000), Cooling::Geometric(0.9), 100, 0.5, 100,
+            vec![TransitionKind::InterCycle, TransitionKind::Edges],
+        );
+        let initial = initial_score(&instance);
+        let (solution, scores) = observed_solve(&solver, &instance);
+        assert_eq!(scores.0.first().unwrap().1, initial);
+        assert!(instance.eval(&solution) < initial / 2);
+
+        // relocations change the cycle sizes within the balance
+        instance.balance = Balance::Tolerance(6);
+        let relocating = SimulatedAnnealingSolver::new(
+            Box::new(RandomSolver::new()),
+            Termination::Iterations(20000), Cooling::Adaptive { alpha: 0.9, patience: 10, reheat: 0.3 }, 100, 0.5, 100,
+            vec![TransitionKind::InterCycle, TransitionKind::Relocate, TransitionKind::Edges],
+        );
+        let (solution, _) = observed_solve(&relocating, &instance);
+        let sizes: Vec<usize> = solution.perms.iter().map(|perm| perm.len()).collect();
+        assert!(instance.balance.allows(&sizes));
+    }
+
+    #[test]
+    fn tabu_search_test() {
+        let mut instance = instance();
+        let solver = TabuSearchSolver::new(
+            Box::new(RandomSolver::new()),
+            Termination::Iterations(2000), (5, 15), 200, 1000, 10,
+            vec![TransitionKind::InterCycle, TransitionKind::Edges],
+        );
+        let initial = initial_score(&instance);
+        let (solution, scores) = observed_solve(&solver, &instance);
+        assert_eq!(scores.0.first().unwrap().1, initial);
+        assert!(instance.eval(&solution) < initial / 3);
+
+        // frequent diversification restarts from the best solution, which is never lost
+        instance.balance = Balance::Tolerance(6);
+        let relocating = TabuSearchSolver::new(
+            Box::new(RandomSolver::new()),
+            Termination::Iterations(2000), (5, 15), 200, 20, 10,
+            vec![TransitionKind::InterCycle, TransitionKind::Relocate, TransitionKind::Edges],
+        );
+        let (solution, _) = observed_solve(&relocating, &instance);
+        let sizes: Vec<usize> = solution.perms.iter().map(|perm| perm.len()).collect();
+        assert!(instance.balance.allows(&sizes));
+    }
+
+    #[test]
+    #[should_panic(expected = "tenure_min <= tenure_max")]
+    fn tabu_tenure_test() {
+        SolversFactory::create_from_json(&json::parse(r#"{"solver": "TabuSearch", "tenure_min": 10, "tenure_max": 5,
+            "initial_solver": {"solver": "Random"}, "time": 10}"#).unwrap());
+    }
+
+    #[test]
+    fn variable_neighborhood_test() {
+        let config = json::parse(r#"["InterCycle", {"type": "OrOpt", "max_segment": 2}, ["Relocate", {"type": "ThreeOpt", "num_neighbors": 5}]]"#).unwrap();
+        let neighborhoods: Vec<Vec<TransitionKind>> = config.members().map(TransitionKind::structure_from_json).collect();
+        assert_eq!(neighborhoods, vec![
+            vec![TransitionKind::InterCycle],
+            vec![TransitionKind::OrOpt(2)],
+            vec![TransitionKind::Relocate, TransitionKind::ThreeOpt(Some(5))],
+        ]);
+
+        let instance = instance();
+        let neighborhoods = vec![vec![TransitionKind::InterCycle], vec![TransitionKind::Edges], vec![TransitionKind::Cross(2)]];
+        let descent = VariableNeighborhoodSolver::new(Box::new(RandomSolver::new()), neighborhoods.clone(), false);
+        let (solution, _) = observed_solve(&descent, &instance);
+        // a local optimum of every structure
+        let mut rng = StdRng::seed_from_u64(3);
+        for kinds in neighborhoods.iter() {
+            let mut neighborhood = Neighborhood::new(TransitionKind::create_all(kinds), &solution, false, &mut rng);
+            while let Some((score, _)) = neighborhood.next(&instance, &solution) {
+                assert!(score >= 0);
+            }
+        }
+
+        let shaking = Shaking { termination: Termination::Iterations(30), min: 2, step: 2, max: 10 };
+        let search = VariableNeighborhoodSolver::with_shaking(Box::new(RandomSolver::new()), neighborhoods, false, shaking);
+        let (improved, scores) = observed_solve(&search, &instance);
+        assert_eq!(scores.0.first().unwrap().1, instance.eval(&solution));
+        assert!(instance.eval(&improved) < instance.eval(&solution));
+    }
+}
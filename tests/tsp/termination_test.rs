@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use imo::tsp::def::TSPInstance;
+    use imo::traits::{Instance, Solver};
+    use imo::tsp::termination::{Termination, Progress};
+    use imo::tsp::random_solver::RandomSolver;
+    use imo::tsp::memory_solver::MemorySolver;
+    use imo::tsp::iterated_solver::IteratedSolver;
+    use imo::tsp::neighborhoods::transition_kind::TransitionKind;
+    use crate::tsp::fixture::Scores;
+
+    #[test]
+    fn criteria_test() {
+        let config = json::parse(r#"{"type": "Any", "criteria": [
+            {"type": "Target", "score": 100},
+            {"type": "All", "criteria": [{"type": "Iterations", "count": 3}, {"type": "Stagnation", "iterations": 3}]}
+        ]}"#).unwrap();
+        let termination = Termination::from_json(&config);
+        let mut progress = Progress::new();
+        progress.evaluation(500);
+        assert!(!termination.reached(&progress));
+        progress.iteration();
+        progress.evaluation(400);
+        progress.iteration();
+        progress.evaluation(450);
+        progress.iteration();
+        progress.evaluation(400);
+        assert_eq!((progress.iterations, progress.evaluations, progress.best), (3, 4, 400));
+        assert!(!termination.reached(&progress));
+        progress.iteration();
+        assert!(termination.reached(&progress));
+
+        let mut progress = Progress::new();
+        progress.evaluation(100);
+        assert!(termination.reached(&progress));
+
+        let legacy = Termination::from_solver_json(&json::parse(r#"{"time": 250}"#).unwrap());
+        assert_eq!(legacy, Termination::WallTime(250.0));
+        assert!(!Termination::Evaluations(2).reached(&progress));
+    }
+
+    #[test]
+    fn iterations_test() {
+        let mut instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
+        instance.no_cycles = 2;
+        let solver = || IteratedSolver::new(
+            Box::new(RandomSolver::new()),
+            Box::new(MemorySolver::new(Box::new(RandomSolver::new()))),
+            Termination::Iterations(5), 0.05, 0.1,
+            vec![TransitionKind::InterCycle, TransitionKind::Edges],
+        );
+        let a = solver().solve(0, &instance, &mut StdRng::seed_from_u64(3));
+        let b = solver().solve(0, &instance, &mut StdRng::seed_from_u64(3));
+        assert!(a.check());
+        assert_eq!(a.perms, b.perms);
+    }
+
+    #[test]
+    fn observer_test() {
+        let mut instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
+        instance.no_cycles = 2;
+        let solver = IteratedSolver::new(
+            Box::new(RandomSolver::new()),
+            Box::new(MemorySolver::new(Box::new(RandomSolver::new()))),
+            Termination::Iterations(10), 0.05, 0.1,
+            vec![TransitionKind::InterCycle, TransitionKind::Edges],
+        );
+        let mut scores = Scores(vec![]);
+        let solution = solver.solve_observed(0, &instance, &mut StdRng::seed_from_u64(3), &mut scores);
+        assert!(scores.0.len() > 1);
+        assert!(scores.0.windows(2).all(|pair| pair[0].0 <= pair[1].0 && pair[0].1 > pair[1].1));
+        assert_eq!(scores.0.last().unwrap().1, instance.eval(&solution));
+
+        let mut scores = Scores(vec![]);
+        let solution = RandomSolver::new().solve_observed(0, &instance, &mut StdRng::seed_from_u64(3), &mut scores);
+        assert_eq!(scores.0, vec![(0, instance.eval(&solution))]);
+    }
+}
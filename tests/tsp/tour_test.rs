@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use imo::tsp::def::{TSPInstance, TSPSolution};
+    use imo::traits::{Instance, Solver};
+    use imo::tsp::solver::GreedySolver;
+    use imo::tsp::pickers::regret_picker::RegretPicker;
+    use imo::tsp::tour::{write_tour, parse_tour};
+    use imo::tsp::tour_solver::TourSolver;
+    use imo::parse_error::ParseErrorKind;
+    use std::env;
+    use std::fs;
+    use std::fs::File;
+
+    fn tour_path(name: &str) -> String {
+        String::from(env::temp_dir().join(name).to_str().unwrap())
+    }
+
+    fn parse_str(name: &str, contents: &str, instance: &TSPInstance) -> ParseErrorKind {
+        let path = tour_path(name);
+        fs::write(&path, contents).unwrap();
+        parse_tour(&path, instance).err().unwrap().reason
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let mut instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
+        for depot in [None, Some(7)] {
+            instance.depot = depot;
+            let solution = GreedySolver::new(Box::new(RegretPicker)).solve(0, &instance, &mut StdRng::seed_from_u64(0));
+            let path = tour_path("imo_round_trip.tour");
+            write_tour(&mut File::create(&path).unwrap(), "kroA100", &instance, &solution).unwrap();
+            let loaded = parse_tour(&path, &instance).unwrap();
+            assert!(loaded.check());
+            assert_eq!(loaded.perms, solution.perms);
+            assert_eq!(instance.eval(&loaded), instance.eval(&solution));
+        }
+    }
+
+    #[test]
+    fn invalid_tour_test() {
+        let mut instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
+        instance.no_cycles = 1;
+        let body: String = (1..=100).map(|v| format!("{}\n", v)).collect();
+        let valid = format!("TYPE : TOUR\nDIMENSION : 100\nTOUR_SECTION\n{}-1\nEOF\n", body);
+        let path = tour_path("imo_valid.tour");
+        fs::write(&path, &valid).unwrap();
+        assert_eq!(parse_tour(&path, &instance).unwrap().perms, vec![(0..100).collect::<Vec<usize>>()]);
+
+        assert!(matches!(parse_str("imo_dimension.tour", &valid.replace("DIMENSION : 100", "DIMENSION : 99"), &instance),
+                         ParseErrorKind::WrongCount("nodes", 100, 99)));
+        assert!(matches!(parse_str("imo_repeated.tour", &valid.replace("\n100\n", "\n1\n"), &instance),
+                         ParseErrorKind::InvalidNode(_)));
+        assert!(matches!(parse_str("imo_missing.tour", &valid.replace("\n100\n", "\n"), &instance),
+                         ParseErrorKind::WrongCount("nodes", 100, 99)));
+        assert!(matches!(parse_str("imo_out_of_range.tour", &valid.replace("\n100\n", "\n101\n"), &instance),
+                         ParseErrorKind::InvalidNode(_)));
+        assert!(matches!(parse_str("imo_split.tour", &valid.replace("\n50\n", "\n50\n-1\n"), &instance),
+                         ParseErrorKind::WrongCount("tours", 1, 2)));
+
+        instance.no_cycles = 2;
+        let split = valid.replace("\n50\n", "\n50\n-1\n");
+        let path = tour_path("imo_two_tours.tour");
+        fs::write(&path, &split).unwrap();
+        let solution: TSPSolution = parse_tour(&path, &instance).unwrap();
+        assert_eq!(solution.perms.iter().map(|perm| perm.len()).collect::<Vec<usize>>(), vec![50, 50]);
+    }
+
+    #[test]
+    fn tour_solver_test() {
+        assert!(matches!(TourSolver::new("data/missing.tour").err().unwrap().reason, ParseErrorKind::Io(_)));
+        let path = tour_path("imo_malformed.tour");
+        fs::write(&path, "TYPE : TOUR\nTOUR_SECTION\n1\nx\n-1\nEOF\n").unwrap();
+        assert!(matches!(TourSolver::new(&path).err().unwrap().reason, ParseErrorKind::InvalidNumber(_)));
+
+        // the tours are checked against every instance before it is solved
+        let mut instance = TSPInstance::parse_file("data/kroA100.tsp").unwrap();
+        let solution = GreedySolver::new(Box::new(RegretPicker)).solve(0, &instance, &mut StdRng::seed_from_u64(0));
+        let path = tour_path("imo_tour_solver.tour");
+        write_tour(&mut File::create(&path).unwrap(), "kroA100", &instance, &solution).unwrap();
+        let solver = TourSolver::new(&path).unwrap();
+        assert!(solver.supports(&instance));
+        assert_eq!(solver.solve(0, &instance, &mut StdRng::seed_from_u64(0)).perms, solution.perms);
+        instance.no_cycles = 3;
+        assert!(!solver.supports(&instance));
+    }
+}
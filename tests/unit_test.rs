@@ -1,5 +1,11 @@
 mod tsp {
+    mod fixture;
     mod greedy_test;
+    mod def_test;
+    mod neighborhood_test;
+    mod tour_test;
+    mod termination_test;
+    mod solver_test;
 }
 mod primes {
     mod primes_test;